@@ -0,0 +1,59 @@
+// waker-cli
+// Copyright (C) 2025 DarkCeptor44
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::{env, path::PathBuf, process::Command};
+
+fn main() {
+    let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap_or_default());
+    let git_dir = manifest_dir.join("../.git");
+
+    // only the workspace checkout has a commit to report, a published package could be inside some unrelated repository
+    let commit = if git_dir.exists() {
+        println!("cargo:rerun-if-changed=../.git/HEAD");
+        println!("cargo:rerun-if-changed=../.git/refs");
+
+        Command::new("git")
+            .args(["rev-parse", "--short", "HEAD"])
+            .current_dir(&manifest_dir)
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .and_then(|output| String::from_utf8(output.stdout).ok())
+            .map(|commit| commit.trim().to_string())
+            .filter(|commit| !commit.is_empty())
+    } else {
+        println!("cargo:rerun-if-changed=build.rs");
+        None
+    }
+    .unwrap_or_else(|| "unknown".into());
+
+    // the CLI's features forward to the library ones, so their names are the same
+    let mut features: Vec<String> = env::vars()
+        .filter_map(|(key, _)| {
+            key.strip_prefix("CARGO_FEATURE_")
+                .filter(|feature| *feature != "DEFAULT")
+                .map(|feature| feature.to_lowercase().replace('_', "-"))
+        })
+        .collect();
+    features.sort();
+
+    println!("cargo:rustc-env=WAKER_GIT_COMMIT={commit}");
+    println!(
+        "cargo:rustc-env=WAKER_TARGET={}",
+        env::var("TARGET").unwrap_or_default()
+    );
+    println!("cargo:rustc-env=WAKER_FEATURES={}", features.join(","));
+}
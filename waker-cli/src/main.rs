@@ -26,6 +26,8 @@
 //! * [GitHub](https://github.com/DarkCeptor44/waker): `cargo install --git https://github.com/DarkCeptor44/waker waker-cli`
 //! * Manually (after cloning the repo locally): `cargo install --path .`
 //!
+//! The `log`, `oui-db` and `socks` features enable the features of the same name in the `waker` library, `wake info` lists the ones the binary was built with.
+//!
 //! ## Usage
 //!
 //! ```bash
@@ -35,23 +37,60 @@
 //! Usage: wake [OPTIONS] [NAME] [COMMAND]
 //!
 //! Commands:
-//!   add     Add machine
-//!   edit    Edit machine
-//!   list    List machines
-//!   remove  Remove one or multiple machine
-//!   help    Print this message or the help of the given subcommand(s)
+//!   add        Add machine
+//!   all        Wake up all machines
+//!   edit       Edit machine
+//!   info       Print version and build information for bug reports
+//!   export     Export the machines to another config file
+//!   import     Import the machines of another config file, the format of the older `wol` CLI is also accepted
+//!   list       List machines
+//!   normalize  Rewrite the config file with normalized MAC addresses
+//!   plan       Wake up the machines of a plan file, options missing from the plan come from the command line
+//!   rename     Rename machine without changing its MAC address
+//!   remove     Remove one or multiple machine
+//!   help       Print this message or the help of the given subcommand(s)
 //!
 //! Arguments:
-//!   [NAME]  Name of the machine to wake up, if the `-n` option is specified then this is the MAC address to send the magic packet to (must be in format `xx:xx:xx:xx:xx:xx`)
+//!   [NAME]  Name of the machine to wake up, if the `-n` option is specified then this is the MAC address to send the magic packet to (must be in format `xx:xx:xx:xx:xx:xx`, `-` reads it from stdin)
 //!
 //! Options:
 //!   -n, --name-as-mac              This tells the CLI to use the name as the MAC address to send the magic packet to
-//!   -b, --bcast-addr <BCAST_ADDR>  The broadcast address to send the magic packet to (must be `IP:PORT` format) [default: 255.255.255.255:9]  
-//!   -B, --bind-addr <BIND_ADDR>    The address to bind the UDP socket to (must be `IP:PORT` format) [default: 0.0.0.0:0]
+//!   -b, --bcast-addr <BCAST_ADDR>  The broadcast address to send the magic packet to (must be `IP:PORT` format) [default: 255.255.255.255:9]
+//!   -B, --bind-addr <BIND_ADDR>    The address to bind the UDP socket to (`IP:PORT` format or just the IP of the interface to send from), overrides the bind address saved for the machine [default: 0.0.0.0:0]
+//!       --repeat <REPEAT>          How many times to send the magic packet, at most 100 [default: 1]
+//!       --wait <WAIT>              After waking a machine wait up to this long for it to come up, like `10s`, `500ms` or `2m`
+//!       --probe <PROBE>            Address the machine listens on to check that it came up with `--wait` (`IP:PORT` format, like `192.168.1.10:22`)
+//!       --at-utc <TIME>            Wait until this time of day in UTC (not local time) before waking the machine, like `02:00` or `2am`
+//!       --in <DURATION>            Wait this long before waking the machine, like `30s`, `30m` or `2h`
+//!       --explain                  Print the equivalent Rust code using the waker library instead of waking the machine
+//!   -c, --config <CONFIG>          Path of the config file to use instead of `waker.json` in the home directory, it must end in `.json`
+//!   -q, --quiet                    Only print errors, useful when running from cron jobs or scripts
 //!   -h, --help                     Print help
 //!   -V, --version                  Print version
 //! ```
 //!
+//! ### Environment
+//!
+//! Machines can also be provided through the `WAKER_MACHINES` environment variable, which is useful for containers without a config file. It accepts either a JSON array in the same format as the config file or `name=mac` pairs separated by `;`, machines from the config file take precedence over ones with the same name:
+//!
+//! ```bash
+//! $ WAKER_MACHINES="nas=01:23:45:67:89:AB;desktop=01:23:45:67:89:CD" wake nas
+//! ```
+//!
+//! ### Plans
+//!
+//! A wake that's done often, like in a runbook, can be saved to a JSON plan file and run with `wake plan <FILE>`. Targets are names of saved machines (matched exactly, ignoring case) or MAC addresses, woken in order, and every other field is optional with the command line options used when it's missing:
+//!
+//! ```json
+//! {
+//!   "targets": ["nas", "01:23:45:67:89:CD"],
+//!   "broadcast_addr": "192.168.1.255:9",
+//!   "bind_addr": "192.168.1.2",
+//!   "repeat": 3,
+//!   "delay": "2s"
+//! }
+//! ```
+//!
 //! ## Benchmarks
 //!
 //! The CLI was benchmarked using [Hyperfine](https://github.com/sharkdp/hyperfine). The profiles used were:
@@ -114,24 +153,53 @@
 
 mod types;
 mod utils;
+mod wol;
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use clap::{Parser, Subcommand};
 use colored::Colorize;
-use configura::{load_config, Config};
+use configura::{errors::ConfigError, load_config, Config};
 use handy::pattern::{is_close_to_upper_bound, string_similarity};
 use inquire::{Confirm, InquireError, MultiSelect, Select, Text};
-use std::{process::exit, str::FromStr};
+use signal_hook::consts::SIGINT;
+use std::{
+    collections::BTreeMap,
+    env,
+    fmt::Write as _,
+    fs,
+    net::IpAddr,
+    path::{Path, PathBuf},
+    process::exit,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
 use tabela::{CellStyle, Table};
-use types::{Data, Machine};
-use utils::{format_machine_changes, format_machine_details, validate_mac, validate_text};
-use waker::{create_magic_packet, wake_device, Mac, WakeOptions};
+use types::{Data, Machine, MergeStrategy, WakePlan, WakeSummary};
+use utils::{
+    delay_until, format_interval, format_machine_changes, format_machine_details,
+    parse_config_path, parse_duration, parse_env_machines, parse_optional_duration, parse_repeat,
+    parse_tags, parse_time_of_day, read_mac, score_machine, unix_now, validate_ip,
+    validate_optional_duration, validate_optional_mac, validate_text,
+};
+use waker::{wait_until_reachable, wake_device, Mac, WakeError, MAX_REPEAT};
+use wol::{parse_any_config, WolData};
+
+/// Environment variable with extra machines to merge with the config file, either as JSON or as `name=mac` pairs separated by `;`
+const MACHINES_ENV: &str = "WAKER_MACHINES";
+
+/// Address the UDP socket is bound to when neither `--bind-addr` nor the machine have one
+const DEFAULT_BIND_ADDR: &str = "0.0.0.0:0";
 
 #[derive(Debug, Parser)]
 #[command(author, version, about, long_about = None)]
 struct App {
     #[arg(
-        help = "Name of the machine to wake up, if the `-n` option is specified then this is the MAC address to send the magic packet to (must be in format `xx:xx:xx:xx:xx:xx`)"
+        help = "Name of the machine to wake up, if the `-n` option is specified then this is the MAC address to send the magic packet to (must be in format `xx:xx:xx:xx:xx:xx`, `-` reads it from stdin)"
     )]
     name: Option<String>,
 
@@ -153,10 +221,74 @@ struct App {
     #[arg(
         short = 'B',
         long,
-        help = "The address to bind the UDP socket to (must be `IP:PORT` format)",
-        default_value = "0.0.0.0:0"
+        help = "The address to bind the UDP socket to (`IP:PORT` format or just the IP of the interface to send from), overrides the bind address saved for the machine [default: 0.0.0.0:0]"
+    )]
+    bind_addr: Option<String>,
+
+    #[arg(
+        long,
+        value_parser = parse_repeat,
+        help = "How many times to send the magic packet, at most 100",
+        default_value_t = 1
+    )]
+    repeat: usize,
+
+    #[arg(
+        long,
+        value_parser = parse_duration,
+        requires = "probe",
+        help = "After waking a machine wait up to this long for it to come up, like `10s`, `500ms` or `2m`"
+    )]
+    wait: Option<Duration>,
+
+    #[arg(
+        long,
+        help = "Address the machine listens on to check that it came up with `--wait` (`IP:PORT` format, like `192.168.1.10:22`)"
+    )]
+    probe: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "TIME",
+        requires = "name",
+        value_parser = parse_time_of_day,
+        help = "Wait until this time of day in UTC (not local time) before waking the machine, like `02:00` or `2am`"
+    )]
+    at_utc: Option<u64>,
+
+    #[arg(
+        long = "in",
+        value_name = "DURATION",
+        requires = "name",
+        conflicts_with = "at_utc",
+        value_parser = parse_duration,
+        help = "Wait this long before waking the machine, like `30s`, `30m` or `2h`"
+    )]
+    delay: Option<Duration>,
+
+    #[arg(
+        long,
+        requires = "name",
+        help = "Print the equivalent Rust code using the waker library instead of waking the machine"
+    )]
+    explain: bool,
+
+    #[arg(
+        short,
+        long,
+        global = true,
+        value_parser = parse_config_path,
+        help = "Path of the config file to use instead of `waker.json` in the home directory, it must end in `.json`"
+    )]
+    config: Option<PathBuf>,
+
+    #[arg(
+        short,
+        long,
+        global = true,
+        help = "Only print errors, useful when running from cron jobs or scripts"
     )]
-    bind_addr: String,
+    quiet: bool,
 
     #[command(subcommand)]
     command: Option<Command>,
@@ -165,7 +297,16 @@ struct App {
 #[derive(Debug, Subcommand)]
 enum Command {
     #[command(about = "Add machine", alias = "a")]
-    Add,
+    Add {
+        #[arg(
+            long,
+            help = "Pre-fill the MAC address with the last one that was woken up"
+        )]
+        from_last: bool,
+    },
+
+    #[command(about = "Wake up all machines")]
+    All,
 
     #[command(about = "Edit machine", alias = "e")]
     Edit {
@@ -173,8 +314,62 @@ enum Command {
         name: Option<String>,
     },
 
+    #[command(about = "Print version and build information for bug reports")]
+    Info,
+
+    #[command(about = "Export the machines to another config file")]
+    Export {
+        #[arg(help = "Path of the config file to write")]
+        path: PathBuf,
+
+        #[arg(
+            long,
+            help = "Write the format of the older `wol` CLI, which only has names and MAC addresses"
+        )]
+        wol: bool,
+    },
+
+    #[command(
+        about = "Import the machines of another config file, the format of the older `wol` CLI is also accepted"
+    )]
+    Import {
+        #[arg(help = "Path of the config file to import")]
+        path: PathBuf,
+
+        #[arg(
+            long,
+            value_enum,
+            default_value_t = MergeStrategy::KeepExisting,
+            help = "How to handle machines with the same name or MAC address as an existing one"
+        )]
+        strategy: MergeStrategy,
+    },
+
     #[command(about = "List machines", alias = "l")]
-    List,
+    List {
+        #[arg(long, help = "Group the machines under their tags")]
+        group_by_tag: bool,
+    },
+
+    #[command(about = "Rewrite the config file with normalized MAC addresses")]
+    Normalize,
+
+    #[command(
+        about = "Wake up the machines of a plan file, options missing from the plan come from the command line"
+    )]
+    Plan {
+        #[arg(help = "Path of the JSON plan file")]
+        path: PathBuf,
+    },
+
+    #[command(about = "Rename machine without changing its MAC address")]
+    Rename {
+        #[arg(help = "Name of the machine to rename")]
+        old: String,
+
+        #[arg(help = "New name of the machine")]
+        new: String,
+    },
 
     #[command(about = "Remove one or multiple machine", alias = "r")]
     Remove {
@@ -192,58 +387,121 @@ fn main() {
 
 fn run() -> Result<()> {
     let args = App::parse();
+
+    if let Some(Command::Info) = args.command {
+        // printed before loading the config file so it works even when the config is broken
+        println!("{}", build_info());
+        return Ok(());
+    }
+
+    if let Some(path) = &args.config {
+        Data::set_path(path.clone());
+    }
+
     let mut config: Data = load_config().context("Failed to load config file")?;
 
-    match args.name {
+    match &args.name {
         Some(name) => {
-            let default_machine = Machine {
-                name: String::new(),
-                mac: Mac::from_str(&name).context("Invalid MAC address")?,
-            };
+            let default_machine;
 
             let machine = if args.name_as_mac {
+                default_machine = Machine::new("", mac_argument(name)?);
                 &default_machine
             } else {
+                config
+                    .extend_from_env()
+                    .context("Failed to read machines from environment")?;
+
+                // nothing is woken so this fails, otherwise a cron job that woke nothing would look successful
                 if config.machines.is_empty() {
-                    println!("No machines found in config file");
-                    return Ok(());
+                    bail!("No machines found in config file");
                 }
 
-                if let Some(mach) = config.find_best_machine(&name) {
-                    mach
-                } else {
-                    println!("No machine found with name: {name}");
-                    return Ok(());
-                }
+                config
+                    .find_best_machine(name)
+                    .with_context(|| format!("No machine found with name: {name}"))?
             };
 
-            wake_machine(machine, &args.bcast_addr, &args.bind_addr)
-                .context("Failed to wake machine")?;
+            if args.explain {
+                let mac = resolve_mac(machine, Mac::from_arp)?;
+                println!("{}", explain_wake(machine, mac, &args));
+                return Ok(());
+            }
+
+            wait_for_schedule(&args)?;
+            let mac = wake_machine(machine, &args).context("Failed to wake machine")?;
+            remember_wakes(&[(machine.name.clone(), mac)], args.quiet);
+
+            if let (Some(timeout), Some(probe)) = (args.wait, &args.probe) {
+                wait_for_machine(machine, probe, timeout, args.quiet)?;
+            }
         }
 
         None => match args.command {
-            Some(Command::Add) => config.add_machine().context("Failed to add machine")?,
+            Some(Command::Add { from_last }) => config
+                .add_machine(from_last)
+                .context("Failed to add machine")?,
+
+            Some(Command::All) => config.wake_all(&args)?,
+
+            // handled before loading the config file
+            Some(Command::Info) => {}
+
+            Some(Command::Export { path, wol }) => config
+                .export_machines(&path, wol)
+                .context("Failed to export machines")?,
+
+            Some(Command::Import { path, strategy }) => config
+                .import_machines(&path, strategy)
+                .context("Failed to import machines")?,
 
             Some(Command::Edit { name }) => config
                 .edit_machine(name)
                 .context("Failed to edit machine")?,
 
-            Some(Command::List) => config.list_machines().context("Failed to list machines")?,
+            Some(Command::List { group_by_tag }) => config
+                .list_machines(group_by_tag)
+                .context("Failed to list machines")?,
+
+            Some(Command::Normalize) => {
+                // MAC addresses are parsed when loading and always saved in lowercase colon form
+                config.save_config()?;
+                println!("{}", "Config file normalized".green());
+            }
+
+            Some(Command::Plan { ref path }) => config.run_plan(path, &args)?,
+
+            Some(Command::Rename { old, new }) => config
+                .rename_machine(&old, &new)
+                .context("Failed to rename machine")?,
 
             Some(Command::Remove { names }) => config
                 .remove_machines(names)
                 .context("Failed to remove machines")?,
 
             None => {
+                config
+                    .extend_from_env()
+                    .context("Failed to read machines from environment")?;
+
                 if config.machines.is_empty() {
                     println!("No machines found in config file");
                     return Ok(());
                 }
 
                 let machines = config.machines;
-                match Select::new("Choose a machine to wake up:", machines).prompt() {
-                    Ok(mach) => wake_machine(&mach, &args.bcast_addr, &args.bind_addr)
-                        .context("Failed to wake machine")?,
+                match Select::new("Choose a machine to wake up:", machines)
+                    .with_scorer(&|input, machine, _, _| score_machine(input, machine))
+                    .prompt()
+                {
+                    Ok(mach) => {
+                        let mac = wake_machine(&mach, &args).context("Failed to wake machine")?;
+                        remember_wakes(&[(mach.name.clone(), mac)], args.quiet);
+
+                        if let (Some(timeout), Some(probe)) = (args.wait, &args.probe) {
+                            wait_for_machine(&mach, probe, timeout, args.quiet)?;
+                        }
+                    }
                     Err(InquireError::OperationInterrupted | InquireError::OperationCanceled) => {
                         return Ok(())
                     }
@@ -257,9 +515,13 @@ fn run() -> Result<()> {
 }
 
 impl Data {
-    fn add_machine(&mut self) -> Result<()> {
+    fn add_machine(&mut self, from_last: bool) -> Result<()> {
+        if from_last && self.last_mac.is_none() {
+            println!("No MAC address has been woken up yet");
+        }
+
         let Some(machine) = self
-            .prompt_machine(None)
+            .prompt_machine(None, from_last)
             .context("Failed to prompt a machine")?
         else {
             return Ok(());
@@ -271,7 +533,7 @@ impl Data {
             .prompt()?
         {
             self.machines.push(machine);
-            self.save().context("Failed to save config file")?;
+            self.save_config()?;
 
             println!("{}", "Machine added successfully".green());
         }
@@ -281,31 +543,24 @@ impl Data {
 
     fn edit_machine(&mut self, name: Option<String>) -> Result<()> {
         if self.machines.is_empty() {
-            println!("No machines found in config file");
-            return Ok(());
+            bail!("No machines found in config file");
         }
 
         let machine_index = if let Some(name) = name {
-            if let Some(index) = self.find_best_machine_index(&name) {
-                index
-            } else {
-                println!("No machine found with name: {name}");
-                return Ok(());
-            }
+            self.find_best_machine_index(&name)
+                .with_context(|| format!("No machine found with name: {name}"))?
+        } else if let Some(index) = self
+            .prompt_for_machine_index()
+            .context("Failed to prompt for a machine")?
+        {
+            index
         } else {
-            if let Some(index) = self
-                .prompt_for_machine_index()
-                .context("Failed to prompt for a machine")?
-            {
-                index
-            } else {
-                println!("No machine selected");
-                return Ok(());
-            }
+            println!("No machine selected");
+            return Ok(());
         };
 
         let Some(new_machine) = self
-            .prompt_machine(Some(&self.machines[machine_index]))
+            .prompt_machine(Some(&self.machines[machine_index]), false)
             .context("Failed to prompt a machine")?
         else {
             return Ok(());
@@ -325,7 +580,7 @@ impl Data {
             .prompt()?
         {
             self.machines[machine_index] = new_machine;
-            self.save().context("Failed to save config file")?;
+            self.save_config()?;
 
             println!("{}", "Machine edited successfully".green());
         } else {
@@ -335,6 +590,67 @@ impl Data {
         Ok(())
     }
 
+    fn extend_from_env(&mut self) -> Result<()> {
+        let Ok(value) = env::var(MACHINES_ENV) else {
+            return Ok(());
+        };
+
+        let machines = parse_env_machines(&value)
+            .with_context(|| format!("Invalid value for {MACHINES_ENV}"))?;
+        self.extend_machines(machines);
+        Ok(())
+    }
+
+    fn extend_machines(&mut self, machines: Vec<Machine>) {
+        for machine in machines {
+            if !self
+                .machines
+                .iter()
+                .any(|m| m.name.eq_ignore_ascii_case(&machine.name))
+            {
+                self.machines.push(machine);
+            }
+        }
+    }
+
+    fn import_machines(&mut self, path: &Path, strategy: MergeStrategy) -> Result<()> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let other = parse_any_config(&content)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+
+        let before = self.machines.clone();
+        self.merge(other, strategy);
+
+        if self.machines == before {
+            println!("No new machines to import");
+            return Ok(());
+        }
+
+        self.save_config()?;
+        println!(
+            "{}",
+            format!("Imported machines from {}", path.display()).green()
+        );
+        Ok(())
+    }
+
+    fn export_machines(&self, path: &Path, wol: bool) -> Result<()> {
+        let content = if wol {
+            serde_json::to_string_pretty(&WolData::from(self))
+        } else {
+            serde_json::to_string_pretty(self)
+        }
+        .context("Failed to serialize machines")?;
+
+        fs::write(path, content).with_context(|| format!("Failed to write {}", path.display()))?;
+        println!(
+            "{}",
+            format!("Exported machines to {}", path.display()).green()
+        );
+        Ok(())
+    }
+
     fn find_best_machine_index(&self, name: &str) -> Option<usize> {
         let mut best_score = 0.0;
         let mut best_match_index = None;
@@ -375,25 +691,66 @@ impl Data {
         best_match
     }
 
-    fn list_machines(&self) -> Result<()> {
+    /// Groups the machines under each of their tags sorted by tag, machines without tags go last under `(untagged)`
+    fn group_by_tag(&self) -> Vec<(String, Vec<&Machine>)> {
+        let mut groups: BTreeMap<&str, Vec<&Machine>> = BTreeMap::new();
+        let mut untagged = Vec::new();
+
+        for machine in &self.machines {
+            if machine.tags.is_empty() {
+                untagged.push(machine);
+            }
+
+            for tag in &machine.tags {
+                groups.entry(tag).or_default().push(machine);
+            }
+        }
+
+        let mut groups: Vec<(String, Vec<&Machine>)> = groups
+            .into_iter()
+            .map(|(tag, machines)| (tag.to_string(), machines))
+            .collect();
+
+        if !untagged.is_empty() {
+            groups.push(("(untagged)".to_string(), untagged));
+        }
+
+        groups
+    }
+
+    fn list_machines(&self, group_by_tag: bool) -> Result<()> {
         if self.machines.is_empty() {
             println!("No machines found in config file");
             return Ok(());
         }
 
-        let machines: Vec<&Machine> = self.machines.iter().collect();
-        let table = Table::new(&machines)
-            .with_header(&["Name", "MAC"], None, Some(CellStyle::Bold), None)
-            .with_separator("  ");
+        if !group_by_tag {
+            let machines: Vec<&Machine> = self.machines.iter().collect();
+            println!("{}", format_machine_table(&machines)?);
+            return Ok(());
+        }
+
+        for (tag, machines) in self.group_by_tag() {
+            println!("{}", tag.bold());
+            println!("{}", format_machine_table(&machines)?);
+        }
 
-        println!(
-            "{}",
-            table.format().context("Failed to format machine list")?
-        );
         Ok(())
     }
 
-    fn prompt_machine(&self, existing: Option<&Machine>) -> Result<Option<Machine>> {
+    fn initial_mac(&self, existing: Option<&Machine>, from_last: bool) -> String {
+        match (existing, self.last_mac) {
+            (Some(m), _) => m.mac.map(|mac| mac.to_string()).unwrap_or_default(),
+            (None, Some(mac)) if from_last => mac.to_string(),
+            (None, _) => String::new(),
+        }
+    }
+
+    fn prompt_machine(
+        &self,
+        existing: Option<&Machine>,
+        from_last: bool,
+    ) -> Result<Option<Machine>> {
         let default_name = existing.map_or("", |m| &m.name);
         let name = match Text::new("Machine name:")
             .with_initial_value(default_name)
@@ -417,10 +774,10 @@ impl Data {
             return Ok(None);
         }
 
-        let default_mac = existing.map_or(String::new(), |m| m.mac.to_string());
-        let mac = match Text::new("MAC address:")
+        let default_mac = self.initial_mac(existing, from_last);
+        let mac = match Text::new("MAC address (optional if the IP address is set):")
             .with_initial_value(&default_mac)
-            .with_validator(validate_mac)
+            .with_validator(validate_optional_mac)
             .prompt()
         {
             Ok(m) => m,
@@ -430,16 +787,89 @@ impl Data {
             Err(e) => return Err(e.into()),
         };
 
-        Ok(Some(Machine {
-            name,
-            mac: Mac::from_str(&mac).context("Invalid MAC address")?,
-        }))
+        let default_ip = existing
+            .and_then(|m| m.ip)
+            .map(|ip| ip.to_string())
+            .unwrap_or_default();
+        let Some(ip) = prompt_text(
+            Text::new("IP address (optional, used to find the MAC address in the ARP cache):")
+                .with_initial_value(&default_ip)
+                .with_validator(validate_ip),
+        )?
+        else {
+            return Ok(None);
+        };
+
+        if mac.trim().is_empty() && ip.trim().is_empty() {
+            println!("Either a MAC address or an IP address is required");
+            return Ok(None);
+        }
+
+        let default_tags = existing.map(|m| m.tags.join(", ")).unwrap_or_default();
+        let Some(tags) = prompt_text(
+            Text::new("Tags (optional, comma separated):").with_initial_value(&default_tags),
+        )?
+        else {
+            return Ok(None);
+        };
+
+        let default_bind = existing
+            .and_then(|m| m.bind_addr.as_deref())
+            .unwrap_or_default();
+        let Some(bind_addr) = prompt_text(
+            Text::new("Bind address (optional, IP of the interface to send from):")
+                .with_initial_value(default_bind),
+        )?
+        else {
+            return Ok(None);
+        };
+
+        let default_remind = existing
+            .and_then(|m| m.remind_after)
+            .map(format_interval)
+            .unwrap_or_default();
+        let Some(remind_after) = prompt_text(
+            Text::new("Reminder after waking (optional, like `30m` or `2h`):")
+                .with_initial_value(&default_remind)
+                .with_validator(validate_optional_duration),
+        )?
+        else {
+            return Ok(None);
+        };
+
+        let default_note = existing.and_then(|m| m.note.as_deref()).unwrap_or_default();
+        let Some(note) =
+            prompt_text(Text::new("Note (optional):").with_initial_value(default_note))?
+        else {
+            return Ok(None);
+        };
+
+        let mac = match mac.trim() {
+            "" => None,
+            mac => Some(Mac::from_str(mac).context("Invalid MAC address")?),
+        };
+
+        let mut machine = Machine::new(name, mac);
+        machine.ip = match ip.trim() {
+            "" => None,
+            ip => Some(ip.parse().context("Invalid IP address")?),
+        };
+        machine.note = Some(note.trim().to_string()).filter(|n| !n.is_empty());
+        machine.last_woken = existing.and_then(|m| m.last_woken);
+        machine.remind_after = parse_optional_duration(&remind_after)?;
+        machine.tags = parse_tags(&tags);
+        machine.bind_addr = Some(bind_addr.trim().to_string()).filter(|b| !b.is_empty());
+
+        Ok(Some(machine))
     }
 
     fn prompt_for_machine_index(&self) -> Result<Option<usize>> {
         let machines: Vec<&Machine> = self.machines.iter().collect();
 
-        match Select::new("Choose a machine:", machines).prompt() {
+        match Select::new("Choose a machine:", machines)
+            .with_scorer(&|input, machine, _, _| score_machine(input, machine))
+            .prompt()
+        {
             Ok(choice) => {
                 let index = self.machines.iter().position(|m| m == choice);
                 Ok(index)
@@ -452,7 +882,10 @@ impl Data {
     fn prompt_for_machines_index(&self) -> Result<Option<Vec<usize>>> {
         let machines: Vec<&Machine> = self.machines.iter().collect();
 
-        match MultiSelect::new("Choose one or multiple machines:", machines).prompt() {
+        match MultiSelect::new("Choose one or multiple machines:", machines)
+            .with_scorer(&|input, machine, _, _| score_machine(input, machine))
+            .prompt()
+        {
             Ok(choices) => {
                 let indexes = choices
                     .iter()
@@ -465,6 +898,46 @@ impl Data {
         }
     }
 
+    fn rename_machine(&mut self, old: &str, new: &str) -> Result<()> {
+        if self.machines.is_empty() {
+            println!("No machines found in config file");
+            return Ok(());
+        }
+
+        let index = self.apply_rename(old, new)?;
+        self.save_config()?;
+
+        println!(
+            "{}",
+            format!("Machine renamed to {}", self.machines[index].name).green()
+        );
+        Ok(())
+    }
+
+    fn apply_rename(&mut self, old: &str, new: &str) -> Result<usize> {
+        let new = new.trim();
+
+        if new.is_empty() {
+            bail!("Machine name cannot be empty");
+        }
+
+        let index = self
+            .find_best_machine_index(old)
+            .with_context(|| format!("No machine found with name: {old}"))?;
+
+        if self
+            .machines
+            .iter()
+            .enumerate()
+            .any(|(i, m)| i != index && string_similarity(&m.name, new) > 0.9)
+        {
+            bail!("Machine already exists: {new}");
+        }
+
+        self.machines[index].name = new.to_string();
+        Ok(index)
+    }
+
     fn remove_machines(&mut self, names: Option<Vec<String>>) -> Result<()> {
         if self.machines.is_empty() {
             println!("No machines found in config file");
@@ -511,7 +984,7 @@ impl Data {
             if self.machines.len() == initial_len {
                 println!("No machines removed");
             } else {
-                self.save().context("Failed to save config file")?;
+                self.save_config()?;
 
                 println!(
                     "{}",
@@ -522,26 +995,611 @@ impl Data {
 
         Ok(())
     }
+
+    fn save_config(&self) -> Result<()> {
+        match self.save() {
+            Ok(()) => Ok(()),
+            Err(ConfigError::Io(e)) => {
+                let path = self
+                    .path()
+                    .map_or_else(|_| "waker.json".into(), |path| path.display().to_string());
+
+                Err(e).with_context(|| format!("Failed to write config file {path}, use `--config` to point to a writable location or fix the permissions of its directory"))
+            }
+            Err(e) => Err(e).context("Failed to save config file"),
+        }
+    }
+
+    /// Remembers the last woken MAC address and when the saved machines were woken, returning whether anything changed enough to be saved
+    fn record_wakes(&mut self, woken: &[(String, Mac)], now: u64) -> bool {
+        let mut changed = false;
+
+        for (name, mac) in woken {
+            if self.last_mac != Some(*mac) {
+                self.last_mac = Some(*mac);
+                changed = true;
+            }
+
+            if let Some(saved) = self
+                .machines
+                .iter_mut()
+                .find(|m| m.name == *name && m.mac.unwrap_or(*mac) == *mac)
+            {
+                // caches the MAC address resolved from the IP so the next wake doesn't need the ARP cache
+                if saved.mac != Some(*mac) {
+                    saved.mac = Some(*mac);
+                    changed = true;
+                }
+
+                // `list` shows anything under a minute as "just now" so a more recent wake isn't worth a write
+                if saved
+                    .last_woken
+                    .map_or(true, |last| now.saturating_sub(last) >= 60)
+                {
+                    saved.last_woken = Some(now);
+                    changed = true;
+                }
+            }
+        }
+
+        changed
+    }
+
+    fn wake_all(&mut self, args: &App) -> Result<()> {
+        self.extend_from_env()
+            .context("Failed to read machines from environment")?;
+
+        if self.machines.is_empty() {
+            bail!("No machines found in config file");
+        }
+
+        let mut woken = Vec::new();
+        let summary = wake_machines(&self.machines, args.quiet, |machine| {
+            let mac = wake_machine(machine, args)?;
+            woken.push((machine.name.clone(), mac));
+            Ok(())
+        });
+
+        remember_wakes(&woken, args.quiet);
+        report_summary(&summary, args.quiet)
+    }
+
+    fn run_plan(&mut self, path: &Path, args: &App) -> Result<()> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let plan: WakePlan = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+
+        if let Some(count) = plan.repeat.filter(|&count| count > MAX_REPEAT) {
+            return Err(WakeError::RepeatTooLarge {
+                count,
+                max: MAX_REPEAT,
+            })
+            .with_context(|| format!("Invalid plan {}", path.display()));
+        }
+
+        self.extend_from_env()
+            .context("Failed to read machines from environment")?;
+        let machines = self.plan_machines(&plan)?;
+
+        let broadcast = plan.broadcast_addr.as_deref().unwrap_or(&args.bcast_addr);
+        let repeat = plan.repeat.unwrap_or(args.repeat);
+        let mut first = true;
+        let mut woken = Vec::new();
+
+        let summary = wake_machines(&machines, args.quiet, |machine| {
+            if let Some(delay) = plan.delay.filter(|_| !first) {
+                thread::sleep(delay);
+            }
+            first = false;
+
+            let bind = plan
+                .bind_addr
+                .as_deref()
+                .unwrap_or_else(|| bind_address(machine, args));
+            let mac = send_wake(machine, broadcast, bind, repeat, args.quiet)?;
+            woken.push((machine.name.clone(), mac));
+            Ok(())
+        });
+
+        remember_wakes(&woken, args.quiet);
+        report_summary(&summary, args.quiet)
+    }
+
+    /// Resolves the targets of the plan, MAC addresses are used as they are and names must match a machine exactly (ignoring case) so a plan never wakes the wrong machine
+    fn plan_machines(&self, plan: &WakePlan) -> Result<Vec<Machine>> {
+        plan.targets
+            .iter()
+            .map(|target| {
+                if let Ok(mac) = Mac::from_str(target) {
+                    return Ok(Machine::new("", mac));
+                }
+
+                self.machines
+                    .iter()
+                    .find(|m| m.name.eq_ignore_ascii_case(target.trim()))
+                    .cloned()
+                    .with_context(|| format!("No machine found with name: {target}"))
+            })
+            .collect()
+    }
+}
+
+fn format_machine_table(machines: &[&Machine]) -> Result<String> {
+    Table::new(machines)
+        .with_header(
+            &["Name", "MAC", "Last woken", "Tags", "Note"],
+            None,
+            Some(CellStyle::Bold),
+            None,
+        )
+        .with_separator("  ")
+        .format()
+        .context("Failed to format machine list")
+}
+
+fn prompt_text(text: Text) -> Result<Option<String>> {
+    match text.prompt() {
+        Ok(s) => Ok(Some(s)),
+        Err(InquireError::OperationInterrupted | InquireError::OperationCanceled) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn remember_wakes(woken: &[(String, Mac)], quiet: bool) {
+    // the machines were already woken up so failing to remember them only warns instead of failing the command,
+    // a fresh copy is loaded so machines from the environment aren't saved to the config file
+    let Ok(mut config) = load_config::<Data>() else {
+        return;
+    };
+
+    // repeated wakes of the same machine don't rewrite the config file, so a plain wake rarely races with `add` or `edit`
+    if !config.record_wakes(woken, unix_now()) {
+        return;
+    }
+
+    if let Err(e) = config.save_config() {
+        if !quiet {
+            eprintln!(
+                "{}",
+                format!(
+                    "Warning: the wake wasn't remembered, `add --from-last` won't see it: {e:#}"
+                )
+                .yellow()
+            );
+        }
+    }
 }
 
-fn wake_machine(machine: &Machine, bcast_addr: &str, bind_addr: &str) -> Result<()> {
+fn wake_machines<F>(machines: &[Machine], quiet: bool, mut wake: F) -> WakeSummary
+where
+    F: FnMut(&Machine) -> Result<()>,
+{
+    let mut summary = WakeSummary::default();
+
+    for (i, machine) in machines.iter().enumerate() {
+        if !quiet {
+            print!("[{}/{}] ", i + 1, machines.len());
+        }
+
+        match wake(machine) {
+            Ok(()) => summary.succeeded += 1,
+            Err(e) => {
+                summary.failed += 1;
+                eprintln!("{}", format!("{e:?}").red());
+            }
+        }
+    }
+
+    summary
+}
+
+/// Prints the summary of waking several machines, failing the command if any of them failed
+fn report_summary(summary: &WakeSummary, quiet: bool) -> Result<()> {
+    if summary.failed > 0 {
+        bail!("{summary}");
+    }
+
+    if !quiet {
+        println!("{}", summary.to_string().green());
+    }
+
+    Ok(())
+}
+
+fn wait_for_machine(machine: &Machine, probe: &str, timeout: Duration, quiet: bool) -> Result<()> {
+    if !quiet {
+        println!("Waiting up to {timeout:?} for {probe} to come up...");
+    }
+
+    let elapsed = wait_until_reachable(probe, timeout)?;
+    if quiet {
+        return Ok(());
+    }
+
     println!(
-        "Waking up machine{} with MAC address {}...",
-        if machine.name.is_empty() {
-            String::new()
-        } else {
-            format!(" {}", machine.name.green())
-        },
-        format!("{:X}", machine.mac).cyan()
+        "{}",
+        format!(
+            "{} is up after {:.1}s",
+            if machine.name.is_empty() {
+                probe
+            } else {
+                &machine.name
+            },
+            elapsed.as_secs_f64()
+        )
+        .green()
     );
+    Ok(())
+}
 
-    let packet = create_magic_packet(machine.mac)?;
+/// Waits in the foreground until the time set with `--at-utc` or `--in`, Ctrl-C cancels the wake
+fn wait_for_schedule(args: &App) -> Result<()> {
+    let delay = match (args.at_utc, args.delay) {
+        (Some(at), _) => delay_until(at, unix_now()),
+        (None, Some(delay)) => delay,
+        (None, None) => return Ok(()),
+    };
+
+    if delay.is_zero() {
+        return Ok(());
+    }
+
+    // the first Ctrl-C only sets the flag, once it's set (or the wait is over) Ctrl-C exits right away again
+    let interrupted = Arc::new(AtomicBool::new(false));
+    signal_hook::flag::register_conditional_shutdown(SIGINT, 130, Arc::clone(&interrupted))
+        .context("Failed to handle Ctrl-C")?;
+    signal_hook::flag::register(SIGINT, Arc::clone(&interrupted))
+        .context("Failed to handle Ctrl-C")?;
+
+    if !args.quiet {
+        println!("Waiting {delay:?} before waking up, press Ctrl-C to cancel...");
+    }
+
+    let deadline = Instant::now() + delay;
+    while let Some(remaining) = deadline
+        .checked_duration_since(Instant::now())
+        .filter(|r| !r.is_zero())
+    {
+        if interrupted.load(Ordering::Relaxed) {
+            bail!("Cancelled, no magic packet was sent");
+        }
+
+        thread::sleep(remaining.min(Duration::from_millis(100)));
+    }
+
+    if interrupted.swap(true, Ordering::Relaxed) {
+        bail!("Cancelled, no magic packet was sent");
+    }
 
-    wake_device(
-        WakeOptions::new(&packet)
-            .broadcast_address(bcast_addr)
-            .bind_address(bind_addr),
-    )
-    .context("Failed to wake device")?;
     Ok(())
 }
+
+/// Returns the MAC address of the machine, looking it up with `arp` when only its IP address is saved
+fn resolve_mac<F>(machine: &Machine, arp: F) -> Result<Mac>
+where
+    F: FnOnce(IpAddr) -> Result<Mac, WakeError>,
+{
+    if let Some(mac) = machine.mac {
+        return Ok(mac);
+    }
+
+    let Some(ip) = machine.ip else {
+        bail!("Machine {} has no MAC address or IP address", machine.name);
+    };
+
+    arp(ip).with_context(|| {
+        format!(
+            "Failed to find the MAC address of {} ({ip}) in the ARP cache, try reaching it once (e.g. with ping) while it's on or save its MAC address",
+            machine.name
+        )
+    })
+}
+
+fn wake_machine(machine: &Machine, args: &App) -> Result<Mac> {
+    send_wake(
+        machine,
+        &args.bcast_addr,
+        bind_address(machine, args),
+        args.repeat,
+        args.quiet,
+    )
+}
+
+/// Wakes the machine with the given options instead of the command line ones, returning the MAC address the packet was sent to
+fn send_wake(
+    machine: &Machine,
+    broadcast: &str,
+    bind: &str,
+    repeat: usize,
+    quiet: bool,
+) -> Result<Mac> {
+    let mac = resolve_mac(machine, Mac::from_arp)?;
+
+    if !quiet {
+        println!(
+            "Waking up machine{} with MAC address {}...",
+            if machine.name.is_empty() {
+                String::new()
+            } else {
+                format!(" {}", machine.name.green())
+            },
+            format!("{mac:X}").cyan()
+        );
+    }
+
+    let options = machine
+        .wake_target(mac)
+        .wake_options()
+        .broadcast_address(broadcast)
+        .bind_address(bind)
+        .repeat(repeat);
+
+    // warnings are hidden with `--quiet` like the rest of the output, only errors are printed
+    if let (false, Err(e)) = (quiet, options.check_bind()) {
+        eprintln!("{}", format!("Warning: {e}").yellow());
+    }
+
+    wake_device(options).context("Failed to wake device")?;
+    Ok(mac)
+}
+
+/// Builds the Rust code that sends the same magic packet as waking the machine with these arguments
+fn explain_wake(machine: &Machine, mac: Mac, args: &App) -> String {
+    let mut options = format!(
+        "WakeOptions::new(&packet)\n        .broadcast_address({:?})\n        .bind_address({:?})",
+        args.bcast_addr,
+        bind_address(machine, args)
+    );
+
+    if args.repeat > 1 {
+        write!(options, "\n        .repeat({})", args.repeat).unwrap_or_default();
+    }
+
+    format!(
+        "use waker::{{create_magic_packet, wake_device, WakeOptions}};\n\nlet packet = create_magic_packet(\"{mac}\").unwrap();\nwake_device(\n    {options},\n)\n.unwrap();"
+    )
+}
+
+/// Describes the build of the CLI, the commit, target and features are captured by the build script
+fn build_info() -> String {
+    let features = env!("WAKER_FEATURES");
+
+    format!(
+        "wake {}\nCommit: {}\nTarget: {}\nFeatures: {}",
+        env!("CARGO_PKG_VERSION"),
+        env!("WAKER_GIT_COMMIT"),
+        env!("WAKER_TARGET"),
+        if features.is_empty() {
+            "none"
+        } else {
+            features
+        }
+    )
+}
+
+/// Parses the MAC address given with `-n`, `-` reads it from stdin
+fn mac_argument(name: &str) -> Result<Mac> {
+    if name == "-" {
+        read_mac(std::io::stdin().lock())
+    } else {
+        Mac::from_str(name).context("Invalid MAC address")
+    }
+}
+
+/// Picks the bind address for the machine, `--bind-addr` wins over the one saved for the machine
+fn bind_address<'a>(machine: &'a Machine, args: &'a App) -> &'a str {
+    args.bind_addr
+        .as_deref()
+        .or(machine.bind_addr.as_deref().filter(|b| !b.is_empty()))
+        .unwrap_or(DEFAULT_BIND_ADDR)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_env_machines_are_wakeable_by_name() {
+        let mut config = Data {
+            machines: vec![Machine::new(
+                "desktop",
+                Mac([0x01, 0x23, 0x45, 0x67, 0x89, 0xAB]),
+            )],
+            ..Default::default()
+        };
+
+        config.extend_machines(
+            parse_env_machines("nas=AA:BB:CC:DD:EE:FF; desktop=11:22:33:44:55:66")
+                .expect("Failed to parse machines"),
+        );
+
+        assert_eq!(config.machines.len(), 2);
+
+        let nas = config.find_best_machine("nas").expect("Machine not found");
+        assert_eq!(nas.mac, Some(Mac([0xAA, 0xBB, 0xCC, 0xDD, 0xEE, 0xFF])));
+
+        let desktop = config
+            .find_best_machine("desktop")
+            .expect("Machine not found");
+        assert_eq!(desktop.mac, Some(Mac([0x01, 0x23, 0x45, 0x67, 0x89, 0xAB])));
+    }
+
+    #[test]
+    fn test_rename_machine_keeps_mac() {
+        let mut config = Data {
+            machines: vec![
+                Machine::new("nas", Mac([0x01, 0x23, 0x45, 0x67, 0x89, 0xAB])),
+                Machine::new("desktop", Mac([0xAA, 0xBB, 0xCC, 0xDD, 0xEE, 0xFF])),
+            ],
+            ..Default::default()
+        };
+
+        let index = config
+            .apply_rename("nas", "storage")
+            .expect("Failed to rename machine");
+
+        assert_eq!(
+            config.machines[index],
+            Machine::new("storage", Mac([0x01, 0x23, 0x45, 0x67, 0x89, 0xAB]))
+        );
+        assert!(config.apply_rename("storage", "desktop").is_err());
+    }
+
+    #[test]
+    fn test_resolve_mac_from_ip() {
+        let mac = Mac([0x01, 0x23, 0x45, 0x67, 0x89, 0xAB]);
+        let mut machine = Machine::new("nas", None);
+        machine.ip = Some(IpAddr::from([192, 168, 1, 10]));
+
+        let resolved = resolve_mac(&machine, |ip| {
+            assert_eq!(ip, IpAddr::from([192, 168, 1, 10]));
+            Ok(mac)
+        })
+        .expect("Failed to resolve MAC address");
+        assert_eq!(resolved, mac);
+
+        let err = resolve_mac(&machine, |ip| {
+            Err(WakeError::ArpLookup {
+                ip,
+                reason: "not in the ARP cache".into(),
+            })
+        })
+        .unwrap_err();
+        assert!(err.to_string().contains("nas (192.168.1.10)"));
+        assert!(err.downcast_ref::<WakeError>().is_some());
+
+        // a saved MAC address is used without looking up the IP
+        machine.mac = Some(mac);
+        assert_eq!(
+            resolve_mac(&machine, |_| unreachable!()).expect("Failed to resolve MAC address"),
+            mac
+        );
+
+        assert!(resolve_mac(&Machine::new("nas", None), |_| unreachable!()).is_err());
+    }
+
+    #[test]
+    fn test_initial_mac_from_last() {
+        let config = Data {
+            last_mac: Some(Mac([0x01, 0x23, 0x45, 0x67, 0x89, 0xAB])),
+            ..Default::default()
+        };
+
+        assert_eq!(config.initial_mac(None, true), "01:23:45:67:89:ab");
+        assert_eq!(config.initial_mac(None, false), "");
+        assert_eq!(Data::default().initial_mac(None, true), "");
+    }
+
+    #[test]
+    fn test_record_wakes_skips_unchanged() {
+        let mac = Mac([0x01, 0x23, 0x45, 0x67, 0x89, 0xAB]);
+        let mut config = Data {
+            machines: vec![Machine::new("nas", mac), Machine::new("pc", None)],
+            ..Default::default()
+        };
+        let woken = [("nas".to_string(), mac)];
+
+        assert!(config.record_wakes(&woken, 1_700_000_000));
+        assert_eq!(config.last_mac, Some(mac));
+        assert_eq!(config.machines[0].last_woken, Some(1_700_000_000));
+
+        // waking it again within a minute changes nothing worth saving
+        assert!(!config.record_wakes(&woken, 1_700_000_030));
+        assert_eq!(config.machines[0].last_woken, Some(1_700_000_000));
+        assert!(config.record_wakes(&woken, 1_700_000_060));
+
+        // the MAC address resolved for a machine saved with only an IP is cached
+        let other = Mac([0x00, 0x1B, 0x21, 0x12, 0x34, 0x56]);
+        assert!(config.record_wakes(&[("pc".to_string(), other)], 1_700_000_060));
+        assert_eq!(config.machines[1].mac, Some(other));
+        assert_eq!(config.last_mac, Some(other));
+    }
+
+    #[test]
+    fn test_explain_wake() {
+        let mut machine = Machine::new("nas", Mac([0x01, 0x23, 0x45, 0x67, 0x89, 0xAB]));
+        machine.bind_addr = Some("192.168.1.2".into());
+        let args = App::parse_from(["wake", "nas", "--explain", "-b", "192.168.1.255:9"]);
+        let snippet = explain_wake(&machine, machine.mac.unwrap(), &args);
+
+        assert!(snippet.contains(r#"create_magic_packet("01:23:45:67:89:ab")"#));
+        assert!(snippet.contains(r#".broadcast_address("192.168.1.255:9")"#));
+        assert!(snippet.contains(r#".bind_address("192.168.1.2")"#));
+        assert!(!snippet.contains(".repeat("));
+
+        let args = App::parse_from(["wake", "nas", "--explain", "--repeat", "3"]);
+        let snippet = explain_wake(&machine, machine.mac.unwrap(), &args);
+        assert!(snippet.contains(r#".broadcast_address("255.255.255.255:9")"#));
+        assert!(snippet.contains(".repeat(3)"));
+    }
+
+    #[test]
+    fn test_machine_bind_address() {
+        let mut machine = Machine::new("nas", Mac([0x01, 0x23, 0x45, 0x67, 0x89, 0xAB]));
+        let args = App::parse_from(["wake", "nas"]);
+        assert_eq!(bind_address(&machine, &args), DEFAULT_BIND_ADDR);
+
+        machine.bind_addr = Some(String::new());
+        assert_eq!(bind_address(&machine, &args), DEFAULT_BIND_ADDR);
+
+        machine.bind_addr = Some("192.168.1.2".into());
+        assert_eq!(bind_address(&machine, &args), "192.168.1.2");
+
+        let args = App::parse_from(["wake", "nas", "-B", "10.0.0.2"]);
+        assert_eq!(bind_address(&machine, &args), "10.0.0.2");
+    }
+
+    #[test]
+    fn test_wake_machines_summary_counts_failures() {
+        let machines: Vec<Machine> = ["nas", "desktop", "laptop"]
+            .into_iter()
+            .map(|name| Machine::new(name, Mac([0x01, 0x23, 0x45, 0x67, 0x89, 0xAB])))
+            .collect();
+
+        let summary = wake_machines(&machines, false, |machine| {
+            if machine.name == "desktop" {
+                bail!("Failed to wake device");
+            }
+
+            Ok(())
+        });
+
+        assert_eq!(
+            summary,
+            WakeSummary {
+                succeeded: 2,
+                failed: 1
+            }
+        );
+        assert_eq!(summary.to_string(), "Woke up 2 of 3 machines, 1 failed");
+    }
+
+    #[test]
+    fn test_group_machines_by_tag() {
+        let mac = Mac([0x01, 0x23, 0x45, 0x67, 0x89, 0xAB]);
+        let mut nas = Machine::new("nas", mac);
+        nas.tags = parse_tags("storage, lab");
+        let mut desktop = Machine::new("desktop", mac);
+        desktop.tags = parse_tags("lab");
+        let config = Data {
+            machines: vec![nas, desktop, Machine::new("laptop", mac)],
+            ..Default::default()
+        };
+
+        let groups: Vec<(String, Vec<&str>)> = config
+            .group_by_tag()
+            .into_iter()
+            .map(|(tag, machines)| (tag, machines.iter().map(|m| m.name.as_str()).collect()))
+            .collect();
+
+        assert_eq!(
+            groups,
+            [
+                ("lab".to_string(), vec!["nas", "desktop"]),
+                ("storage".to_string(), vec!["nas"]),
+                ("(untagged)".to_string(), vec!["laptop"]),
+            ]
+        );
+    }
+}
@@ -14,32 +14,206 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
+use crate::utils::{format_last_woken, parse_duration};
+use clap::ValueEnum;
 use configura::{formats::JsonFormat, Config};
-use serde::{Deserialize, Serialize};
-use std::fmt::Display;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::{
+    ffi::OsStr,
+    fmt::Display,
+    net::IpAddr,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+    time::Duration,
+};
 use tabela::{Cell, Color, Row};
-use waker::Mac;
+use waker::{Mac, WakeTarget};
 
 const CONFIG_NAME: &str = "waker";
 
+/// Path of the config file set with `--config`, the home directory is used when it isn't set
+static CONFIG_PATH: OnceLock<PathBuf> = OnceLock::new();
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, Default)]
 pub struct Data {
     pub machines: Vec<Machine>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_mac: Option<Mac>,
 }
 
 impl Config for Data {
     type FormatType = JsonFormat;
     type FormatContext = ();
 
-    fn config_path_and_filename(_home_dir: &std::path::Path) -> (Option<std::path::PathBuf>, &str) {
-        (None, CONFIG_NAME)
+    fn config_path_and_filename(_home_dir: &Path) -> (Option<PathBuf>, &str) {
+        match CONFIG_PATH.get() {
+            Some(path) => (
+                path.parent().map(Path::to_path_buf),
+                path.file_stem()
+                    .and_then(OsStr::to_str)
+                    .unwrap_or(CONFIG_NAME),
+            ),
+            None => (None, CONFIG_NAME),
+        }
+    }
+}
+
+impl Data {
+    /// Uses the given config file instead of the one in the home directory, it must have the `.json` extension since that's always used, see [`parse_config_path`](crate::utils::parse_config_path)
+    pub fn set_path(path: PathBuf) {
+        CONFIG_PATH.get_or_init(|| path);
+    }
+
+    /// Merges the machines of another config into this one, machines conflict when they have the same name (ignoring case) or MAC address
+    pub fn merge(&mut self, other: Data, strategy: MergeStrategy) {
+        for mut machine in other.machines {
+            match strategy {
+                MergeStrategy::KeepExisting => {
+                    if !self.machines.iter().any(|m| m.conflicts_with(&machine)) {
+                        self.machines.push(machine);
+                    }
+                }
+
+                MergeStrategy::PreferIncoming => {
+                    match self
+                        .machines
+                        .iter()
+                        .position(|m| m.conflicts_with(&machine))
+                    {
+                        // the incoming machine takes the place of the first one it conflicts with
+                        Some(index) => {
+                            self.machines.retain(|m| !m.conflicts_with(&machine));
+                            self.machines.insert(index, machine);
+                        }
+                        None => self.machines.push(machine),
+                    }
+                }
+
+                MergeStrategy::Rename => {
+                    // a machine with the same MAC address is the same machine so there's nothing to rename
+                    if self
+                        .machines
+                        .iter()
+                        .any(|m| m.mac.is_some() && m.mac == machine.mac)
+                    {
+                        continue;
+                    }
+
+                    machine.name = self.unique_name(&machine.name);
+                    self.machines.push(machine);
+                }
+            }
+        }
+
+        if self.last_mac.is_none() || strategy == MergeStrategy::PreferIncoming {
+            self.last_mac = other.last_mac.or(self.last_mac);
+        }
+    }
+
+    /// Returns the name with the lowest ` (N)` suffix that isn't used by any machine, or the name itself if it's free
+    fn unique_name(&self, name: &str) -> String {
+        let is_taken = |candidate: &str| {
+            self.machines
+                .iter()
+                .any(|m| m.name.eq_ignore_ascii_case(candidate))
+        };
+
+        if !is_taken(name) {
+            return name.to_string();
+        }
+
+        // at most every machine takes one of the names so one of them is always free
+        (2..=self.machines.len() + 2)
+            .map(|n| format!("{name} ({n})"))
+            .find(|candidate| !is_taken(candidate))
+            .unwrap_or_default()
     }
 }
 
+/// How [`Data::merge`] resolves machines that conflict by name or MAC address
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum MergeStrategy {
+    /// Keep the existing machine and drop the incoming one
+    KeepExisting,
+
+    /// Replace the existing machine with the incoming one
+    PreferIncoming,
+
+    /// Keep both by adding a number to the name of the incoming one, machines with the same MAC address are still deduplicated
+    Rename,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Machine {
     pub name: String,
-    pub mac: Mac,
+
+    /// MAC address of the machine, resolved from `ip` in the ARP cache and saved when it's missing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mac: Option<Mac>,
+
+    /// IP address of the machine, only used to find its MAC address when `mac` isn't saved
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ip: Option<IpAddr>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+
+    /// Unix timestamp in seconds of the last time the machine was woken up
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_woken: Option<u64>,
+
+    /// How long after a wake to be reminded about the machine, like to power it off after maintenance, shown next to `last_woken`
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_duration",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub remind_after: Option<Duration>,
+
+    /// Labels used to group machines with `list --group-by-tag`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+
+    /// Address to bind the UDP socket to when waking the machine, empty means the global one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bind_addr: Option<String>,
+}
+
+impl Machine {
+    pub fn new<S, M>(name: S, mac: M) -> Self
+    where
+        S: Into<String>,
+        M: Into<Option<Mac>>,
+    {
+        Self {
+            name: name.into(),
+            mac: mac.into(),
+            ip: None,
+            note: None,
+            last_woken: None,
+            remind_after: None,
+            tags: Vec::new(),
+            bind_addr: None,
+        }
+    }
+
+    /// Builds the target to wake with the machine's name and the given MAC address, which may have been resolved from its IP
+    pub fn wake_target(&self, mac: Mac) -> WakeTarget {
+        let target = WakeTarget::new(mac);
+
+        if self.name.is_empty() {
+            target
+        } else {
+            target.with_name(&self.name)
+        }
+    }
+
+    /// Returns `true` if both machines have the same name (ignoring case) or MAC address
+    fn conflicts_with(&self, other: &Machine) -> bool {
+        self.name.eq_ignore_ascii_case(&other.name) || (self.mac.is_some() && self.mac == other.mac)
+    }
 }
 
 impl Display for Machine {
@@ -52,7 +226,237 @@ impl Row for &Machine {
     fn as_row(&self) -> Vec<Cell> {
         vec![
             Cell::new(&self.name).with_color(Color::Green),
-            Cell::new(self.mac).with_color(Color::Cyan),
+            Cell::new(match (self.mac, self.ip) {
+                (Some(mac), _) => mac.to_string(),
+                (None, Some(ip)) => format!("({ip})"),
+                (None, None) => String::new(),
+            })
+            .with_color(Color::Cyan),
+            Cell::new(format_last_woken(self)),
+            Cell::new(self.tags.join(", ")).with_color(Color::Yellow),
+            Cell::new(self.note.as_deref().unwrap_or_default()),
         ]
     }
 }
+
+/// Machines and options woken together with `wake plan`, so complex wakes can be repeated and shared
+///
+/// Options missing from the plan come from the command line
+#[derive(Debug, Serialize, Deserialize, PartialEq, Default)]
+pub struct WakePlan {
+    /// Names of saved machines or MAC addresses, woken in order
+    pub targets: Vec<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub broadcast_addr: Option<String>,
+
+    /// Overrides the bind address saved for the machines
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bind_addr: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repeat: Option<usize>,
+
+    /// How long to wait between targets, in the same format as `--wait`
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_duration",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub delay: Option<Duration>,
+}
+
+// serde passes a reference to the field
+#[allow(clippy::ref_option)]
+fn serialize_duration<S: Serializer>(
+    duration: &Option<Duration>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    // only called for `Some` because of `skip_serializing_if`
+    serializer.serialize_str(&format!("{}ms", duration.unwrap_or_default().as_millis()))
+}
+
+fn deserialize_duration<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Duration>, D::Error> {
+    Option::<String>::deserialize(deserializer)?
+        .map(|value| parse_duration(&value).map_err(de::Error::custom))
+        .transpose()
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct WakeSummary {
+    pub succeeded: usize,
+    pub failed: usize,
+}
+
+impl Display for WakeSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Woke up {} of {} machines",
+            self.succeeded,
+            self.succeeded + self.failed
+        )?;
+
+        if self.failed > 0 {
+            write!(f, ", {} failed", self.failed)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::format_machine_details;
+
+    #[test]
+    fn test_config_macs_are_normalized() {
+        let config: Data = serde_json::from_str(
+            r#"{"machines":[{"name":"nas","mac":"01-23-45-67-89-AB"},{"name":"desktop","mac":"aa.BB.cc.DD.ee.FF"}]}"#,
+        )
+        .expect("Failed to parse config");
+
+        assert_eq!(
+            serde_json::to_string(&config).expect("Failed to serialize config"),
+            r#"{"machines":[{"name":"nas","mac":"01:23:45:67:89:ab"},{"name":"desktop","mac":"aa:bb:cc:dd:ee:ff"}]}"#
+        );
+    }
+
+    fn merge_configs() -> (Data, Data) {
+        let existing = Data {
+            machines: vec![
+                Machine::new("nas", Mac([0x01, 0x23, 0x45, 0x67, 0x89, 0xAB])),
+                Machine::new("desktop", Mac([0xAA, 0xBB, 0xCC, 0xDD, 0xEE, 0xFF])),
+            ],
+            last_mac: None,
+        };
+        let incoming = Data {
+            machines: vec![
+                // same name as an existing machine
+                Machine::new("NAS", Mac([0x11, 0x22, 0x33, 0x44, 0x55, 0x66])),
+                // same MAC as an existing machine
+                Machine::new("workstation", Mac([0xAA, 0xBB, 0xCC, 0xDD, 0xEE, 0xFF])),
+                Machine::new("laptop", Mac([0x00, 0x1B, 0x21, 0x12, 0x34, 0x56])),
+            ],
+            last_mac: Some(Mac([0x00, 0x1B, 0x21, 0x12, 0x34, 0x56])),
+        };
+
+        (existing, incoming)
+    }
+
+    fn names(config: &Data) -> Vec<&str> {
+        config.machines.iter().map(|m| m.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_merge_keep_existing() {
+        let (mut config, incoming) = merge_configs();
+        config.merge(incoming, MergeStrategy::KeepExisting);
+
+        assert_eq!(names(&config), ["nas", "desktop", "laptop"]);
+        assert_eq!(
+            config.machines[0].mac,
+            Some(Mac([0x01, 0x23, 0x45, 0x67, 0x89, 0xAB]))
+        );
+        assert_eq!(
+            config.last_mac,
+            Some(Mac([0x00, 0x1B, 0x21, 0x12, 0x34, 0x56]))
+        );
+    }
+
+    #[test]
+    fn test_merge_prefer_incoming() {
+        let (mut config, incoming) = merge_configs();
+        config.merge(incoming, MergeStrategy::PreferIncoming);
+
+        assert_eq!(names(&config), ["NAS", "workstation", "laptop"]);
+        assert_eq!(
+            config.machines[0].mac,
+            Some(Mac([0x11, 0x22, 0x33, 0x44, 0x55, 0x66]))
+        );
+    }
+
+    #[test]
+    fn test_merge_prefer_incoming_replaces_every_conflict() {
+        let (mut config, _) = merge_configs();
+        let incoming = Data {
+            // conflicts with nas by name and with desktop by MAC
+            machines: vec![Machine::new(
+                "nas",
+                Mac([0xAA, 0xBB, 0xCC, 0xDD, 0xEE, 0xFF]),
+            )],
+            last_mac: None,
+        };
+        config.merge(incoming, MergeStrategy::PreferIncoming);
+
+        assert_eq!(
+            config.machines,
+            [Machine::new(
+                "nas",
+                Mac([0xAA, 0xBB, 0xCC, 0xDD, 0xEE, 0xFF])
+            )]
+        );
+    }
+
+    #[test]
+    fn test_merge_rename() {
+        let (mut config, mut incoming) = merge_configs();
+        incoming.machines.push(Machine::new(
+            "nas",
+            Mac([0x66, 0x55, 0x44, 0x33, 0x22, 0x11]),
+        ));
+        config.merge(incoming, MergeStrategy::Rename);
+
+        assert_eq!(
+            names(&config),
+            ["nas", "desktop", "NAS (2)", "laptop", "nas (3)"]
+        );
+        assert_eq!(
+            config.machines[2].mac,
+            Some(Mac([0x11, 0x22, 0x33, 0x44, 0x55, 0x66]))
+        );
+    }
+
+    #[test]
+    fn test_machine_note_persists() {
+        let mut machine = Machine::new("nas", Mac([0x01, 0x23, 0x45, 0x67, 0x89, 0xAB]));
+        machine.note = Some("power off after backups".into());
+        machine.last_woken = Some(1_700_000_000);
+
+        let json = serde_json::to_string(&machine).expect("Failed to serialize machine");
+        let parsed: Machine = serde_json::from_str(&json).expect("Failed to parse machine");
+
+        assert_eq!(parsed, machine);
+        assert!(format_machine_details(&parsed).contains("power off after backups"));
+    }
+
+    #[test]
+    fn test_machine_remind_after_persists() {
+        let mut machine = Machine::new("nas", Mac([0x01, 0x23, 0x45, 0x67, 0x89, 0xAB]));
+        machine.remind_after = Some(Duration::from_secs(3600));
+
+        let json = serde_json::to_string(&machine).expect("Failed to serialize machine");
+        assert!(json.contains(r#""remind_after":"3600000ms""#));
+
+        let parsed: Machine = serde_json::from_str(&json).expect("Failed to parse machine");
+        assert_eq!(parsed, machine);
+    }
+
+    #[test]
+    fn test_wake_plan_delay() {
+        let plan: WakePlan = serde_json::from_str(r#"{"targets":["nas"],"delay":"2m"}"#)
+            .expect("Failed to parse plan");
+        assert_eq!(plan.delay, Some(Duration::from_secs(120)));
+        assert_eq!(plan.repeat, None);
+
+        let json = serde_json::to_string(&plan).expect("Failed to serialize plan");
+        assert_eq!(json, r#"{"targets":["nas"],"delay":"120000ms"}"#);
+        assert_eq!(serde_json::from_str::<WakePlan>(&json).unwrap(), plan);
+
+        assert!(serde_json::from_str::<WakePlan>(r#"{"targets":[],"delay":"2d"}"#).is_err());
+    }
+}
@@ -15,10 +15,19 @@
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
 use crate::types::Machine;
+use anyhow::{bail, Context, Result};
 use colored::Colorize;
+use handy::pattern::string_similarity;
 use inquire::validator::Validation;
-use std::{fmt::Write as _, str::FromStr};
-use waker::Mac;
+use std::{
+    fmt::Write as _,
+    io::BufRead,
+    net::IpAddr,
+    path::PathBuf,
+    str::FromStr,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use waker::{parse_machine_line, Mac, WakeError, MAX_REPEAT};
 
 pub fn format_machine_changes(before: &Machine, after: &Machine) -> String {
     let mut s = String::new();
@@ -35,8 +44,70 @@ pub fn format_machine_changes(before: &Machine, after: &Machine) -> String {
         writeln!(
             s,
             "MAC: {} -> {}",
-            before.mac.to_string().red(),
-            after.mac.to_string().green()
+            before.mac.map(|m| m.to_string()).unwrap_or_default().red(),
+            after.mac.map(|m| m.to_string()).unwrap_or_default().green()
+        )
+        .unwrap_or_default();
+    }
+
+    if before.ip != after.ip {
+        writeln!(
+            s,
+            "IP: {} -> {}",
+            before.ip.map(|ip| ip.to_string()).unwrap_or_default().red(),
+            after
+                .ip
+                .map(|ip| ip.to_string())
+                .unwrap_or_default()
+                .green()
+        )
+        .unwrap_or_default();
+    }
+
+    if before.tags != after.tags {
+        writeln!(
+            s,
+            "Tags: {} -> {}",
+            before.tags.join(", ").red(),
+            after.tags.join(", ").green()
+        )
+        .unwrap_or_default();
+    }
+
+    if before.bind_addr != after.bind_addr {
+        writeln!(
+            s,
+            "Bind address: {} -> {}",
+            before.bind_addr.as_deref().unwrap_or_default().red(),
+            after.bind_addr.as_deref().unwrap_or_default().green()
+        )
+        .unwrap_or_default();
+    }
+
+    if before.remind_after != after.remind_after {
+        writeln!(
+            s,
+            "Reminder: {} -> {}",
+            before
+                .remind_after
+                .map(format_interval)
+                .unwrap_or_default()
+                .red(),
+            after
+                .remind_after
+                .map(format_interval)
+                .unwrap_or_default()
+                .green()
+        )
+        .unwrap_or_default();
+    }
+
+    if before.note != after.note {
+        writeln!(
+            s,
+            "Note: {} -> {}",
+            before.note.as_deref().unwrap_or_default().red(),
+            after.note.as_deref().unwrap_or_default().green()
         )
         .unwrap_or_default();
     }
@@ -45,21 +116,312 @@ pub fn format_machine_changes(before: &Machine, after: &Machine) -> String {
 }
 
 pub fn format_machine_details(machine: &Machine) -> String {
-    format!(
-        "\nName: {}\nMAC: {}\n",
-        machine.name.green(),
-        machine.mac.to_string().cyan()
-    )
+    let mut s = format!("\nName: {}\n", machine.name.green());
+
+    if let Some(mac) = machine.mac {
+        writeln!(s, "MAC: {}", mac.to_string().cyan()).unwrap_or_default();
+    }
+
+    if let Some(ip) = machine.ip {
+        writeln!(s, "IP: {ip}").unwrap_or_default();
+    }
+
+    if machine.last_woken.is_some() {
+        writeln!(s, "Last woken: {}", format_last_woken(machine)).unwrap_or_default();
+    } else if let Some(interval) = machine.remind_after {
+        writeln!(s, "Reminder: {} after waking", format_interval(interval)).unwrap_or_default();
+    }
+
+    if !machine.tags.is_empty() {
+        writeln!(s, "Tags: {}", machine.tags.join(", ")).unwrap_or_default();
+    }
+
+    if let Some(bind_addr) = machine.bind_addr.as_deref().filter(|b| !b.is_empty()) {
+        writeln!(s, "Bind address: {bind_addr}").unwrap_or_default();
+    }
+
+    if let Some(note) = &machine.note {
+        writeln!(s, "Note: {note}").unwrap_or_default();
+    }
+
+    s
+}
+
+/// Formats when the machine was last woken, followed by whether its reminder is due if it has one
+pub fn format_last_woken(machine: &Machine) -> String {
+    let Some(last_woken) = machine.last_woken else {
+        return String::new();
+    };
+    let ago = format_time_ago(last_woken);
+
+    match machine.remind_after {
+        Some(interval) if unix_now().saturating_sub(last_woken) >= interval.as_secs() => {
+            format!("{ago} (reminder due)")
+        }
+        Some(interval) => format!("{ago} (remind after {})", format_interval(interval)),
+        None => ago,
+    }
+}
+
+/// Formats a duration in the largest unit [`parse_duration`] accepts that fits it exactly, like `90m` or `2h`
+pub fn format_interval(duration: Duration) -> String {
+    let secs = duration.as_secs();
+
+    if duration.subsec_millis() > 0 {
+        format!("{}ms", duration.as_millis())
+    } else if secs > 0 && secs % 3600 == 0 {
+        format!("{}h", secs / 3600)
+    } else if secs > 0 && secs % 60 == 0 {
+        format!("{}m", secs / 60)
+    } else {
+        format!("{secs}s")
+    }
+}
+
+pub fn format_time_ago(timestamp: u64) -> String {
+    let elapsed = unix_now().saturating_sub(timestamp);
+
+    match elapsed {
+        0..60 => "just now".to_string(),
+        60..3600 => format!("{}m ago", elapsed / 60),
+        3600..86400 => format!("{}h ago", elapsed / 3600),
+        _ => format!("{}d ago", elapsed / 86400),
+    }
+}
+
+/// Parses a duration like `500ms`, `10s`, `2m` or `1h`, a number without a unit is in seconds
+pub fn parse_duration(value: &str) -> Result<Duration, String> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: u64 = number
+        .parse()
+        .map_err(|_| format!("Invalid duration: {value}"))?;
+
+    match unit.trim() {
+        "ms" => Ok(Duration::from_millis(number)),
+        "" | "s" => Ok(Duration::from_secs(number)),
+        "m" => number
+            .checked_mul(60)
+            .map(Duration::from_secs)
+            .ok_or_else(|| format!("Duration is too long: {value}")),
+        "h" => number
+            .checked_mul(3600)
+            .map(Duration::from_secs)
+            .ok_or_else(|| format!("Duration is too long: {value}")),
+        unit => Err(format!(
+            "Invalid duration unit `{unit}`, expected ms, s, m or h"
+        )),
+    }
+}
+
+/// Same as [`parse_duration`] but an empty value is [`None`], for the optional fields of the machine prompts
+pub fn parse_optional_duration(value: &str) -> Result<Option<Duration>> {
+    match value.trim() {
+        "" => Ok(None),
+        duration => parse_duration(duration)
+            .map(Some)
+            .map_err(anyhow::Error::msg),
+    }
+}
+
+/// Parses a time of day like `02:00`, `14:30`, `2am` or `2:30pm` into seconds since midnight
+pub fn parse_time_of_day(value: &str) -> Result<u64, String> {
+    let invalid = || format!("Invalid time of day: {value}, expected `HH:MM`, `2am` or `2:30pm`");
+    let lower = value.trim().to_ascii_lowercase();
+    let (time, meridiem) = match lower.strip_suffix("am") {
+        Some(time) => (time, Some(0)),
+        None => match lower.strip_suffix("pm") {
+            Some(time) => (time, Some(12)),
+            None => (lower.as_str(), None),
+        },
+    };
+
+    let (hours, minutes) = time.trim().split_once(':').unwrap_or((time.trim(), "0"));
+    let hours: u64 = hours.parse().map_err(|_| invalid())?;
+    let minutes: u64 = minutes.parse().map_err(|_| invalid())?;
+
+    let hours = match meridiem {
+        // 12am is midnight and 12pm is noon
+        Some(offset) if (1..=12).contains(&hours) => hours % 12 + offset,
+        None if hours < 24 => hours,
+        _ => return Err(invalid()),
+    };
+
+    if minutes >= 60 {
+        return Err(invalid());
+    }
+
+    Ok(hours * 3600 + minutes * 60)
+}
+
+/// Returns how long it is from the Unix timestamp `now` until the next time the UTC clock shows the time of day, zero if it's exactly now
+pub fn delay_until(time_of_day: u64, now: u64) -> Duration {
+    const DAY: u64 = 24 * 3600;
+
+    Duration::from_secs((time_of_day + DAY - now % DAY) % DAY)
+}
+
+/// Parses the `--config` path, which must end in `.json` because the config file is always read from and written to a `.json` file
+pub fn parse_config_path(value: &str) -> Result<PathBuf, String> {
+    let path = PathBuf::from(value);
+
+    if path.extension().and_then(|e| e.to_str()) != Some("json") {
+        return Err(format!(
+            "The config file must have the `.json` extension, got {value}"
+        ));
+    }
+
+    Ok(path)
+}
+
+/// Parses the repeat count, counts above the library's cap are rejected here so the mistake is reported before anything is sent
+pub fn parse_repeat(value: &str) -> Result<usize, String> {
+    let count: usize = value
+        .trim()
+        .parse()
+        .map_err(|_| format!("Invalid repeat count: {value}"))?;
+
+    if count > MAX_REPEAT {
+        return Err(WakeError::RepeatTooLarge {
+            count,
+            max: MAX_REPEAT,
+        }
+        .to_string());
+    }
+
+    Ok(count)
+}
+
+/// Scores how well a machine's name matches what was typed in the selector, `None` hides it
+///
+/// Names starting with the input are ranked above the ones that are only similar to it
+pub fn score_machine(input: &str, machine: &Machine) -> Option<i64> {
+    const MIN_SIMILARITY: f64 = 0.75;
+
+    let input = input.trim();
+    if input.is_empty() {
+        return Some(0);
+    }
+
+    let similarity = string_similarity(&machine.name, input);
+    if similarity < MIN_SIMILARITY {
+        return None;
+    }
+
+    let is_prefix = machine
+        .name
+        .to_lowercase()
+        .starts_with(&input.to_lowercase());
+
+    #[allow(clippy::cast_possible_truncation)]
+    let score = (similarity * 1000.0).round() as i64;
+    Some(if is_prefix { score + 1000 } else { score })
+}
+
+/// Parses comma separated tags, ignoring empty ones and duplicates
+pub fn parse_tags(value: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+
+    for tag in value.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+        if !tags.iter().any(|t| t == tag) {
+            tags.push(tag.to_string());
+        }
+    }
+
+    tags
+}
+
+/// Reads a MAC address from the first non-empty line, for `wake -n -` in pipelines
+pub fn read_mac<R>(reader: R) -> Result<Mac>
+where
+    R: BufRead,
+{
+    for line in reader.lines() {
+        let line = line.context("Failed to read MAC address from stdin")?;
+        let line = line.trim();
+
+        if !line.is_empty() {
+            return Mac::from_str(line).with_context(|| format!("Invalid MAC address: {line}"));
+        }
+    }
+
+    bail!("No MAC address in stdin")
+}
+
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// Parses machines from either a JSON array of machines or `name=mac` pairs separated by `;`
+pub fn parse_env_machines(value: &str) -> Result<Vec<Machine>> {
+    let value = value.trim();
+
+    if value.starts_with('[') {
+        return serde_json::from_str(value).context("Failed to parse machines as JSON");
+    }
+
+    value
+        .split(';')
+        .map(str::trim)
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (name, mac) = parse_machine_line(pair)
+                .with_context(|| format!("Invalid machine in environment: {pair}"))?;
+
+            Ok(Machine::new(name, mac))
+        })
+        .collect()
 }
 
 #[allow(clippy::unnecessary_wraps)]
 pub fn validate_mac(input: &str) -> Result<Validation, Box<dyn std::error::Error + Send + Sync>> {
-    match Mac::from_str(input) {
-        Ok(_) => Ok(Validation::Valid),
+    match Mac::validate(input) {
+        Ok(()) => Ok(Validation::Valid),
         Err(e) => Ok(Validation::Invalid(e.to_string().into())),
     }
 }
 
+/// Same as [`validate_mac`] but an empty input is valid, for machines that are woken by their IP address
+pub fn validate_optional_mac(
+    input: &str,
+) -> Result<Validation, Box<dyn std::error::Error + Send + Sync>> {
+    if input.trim().is_empty() {
+        Ok(Validation::Valid)
+    } else {
+        validate_mac(input)
+    }
+}
+
+#[allow(clippy::unnecessary_wraps)]
+pub fn validate_ip(input: &str) -> Result<Validation, Box<dyn std::error::Error + Send + Sync>> {
+    match input.trim() {
+        "" => Ok(Validation::Valid),
+        ip if ip.parse::<IpAddr>().is_ok() => Ok(Validation::Valid),
+        ip => Ok(Validation::Invalid(
+            format!("Invalid IP address: {ip}").into(),
+        )),
+    }
+}
+
+#[allow(clippy::unnecessary_wraps)]
+pub fn validate_optional_duration(
+    input: &str,
+) -> Result<Validation, Box<dyn std::error::Error + Send + Sync>> {
+    match input.trim() {
+        "" => Ok(Validation::Valid),
+        duration => match parse_duration(duration) {
+            Ok(_) => Ok(Validation::Valid),
+            Err(e) => Ok(Validation::Invalid(e.into())),
+        },
+    }
+}
+
 #[allow(clippy::unnecessary_wraps)]
 pub fn validate_text(input: &str) -> Result<Validation, Box<dyn std::error::Error + Send + Sync>> {
     if input.trim().is_empty() {
@@ -68,3 +430,163 @@ pub fn validate_text(input: &str) -> Result<Validation, Box<dyn std::error::Erro
         Ok(Validation::Valid)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_mac() {
+        assert_eq!(
+            read_mac("\n01:23:45:67:89:AB\n".as_bytes()).unwrap(),
+            Mac([0x01, 0x23, 0x45, 0x67, 0x89, 0xAB])
+        );
+        assert_eq!(
+            read_mac("  \n".as_bytes()).unwrap_err().to_string(),
+            "No MAC address in stdin"
+        );
+        assert_eq!(
+            read_mac("nas".as_bytes()).unwrap_err().to_string(),
+            "Invalid MAC address: nas"
+        );
+    }
+
+    #[test]
+    fn test_parse_env_machines_json() {
+        let machines = parse_env_machines(r#"[{"name":"nas","mac":"01:23:45:67:89:AB"}]"#)
+            .expect("Failed to parse machines");
+
+        assert_eq!(
+            machines,
+            vec![Machine::new(
+                "nas",
+                Mac([0x01, 0x23, 0x45, 0x67, 0x89, 0xAB])
+            )]
+        );
+    }
+
+    #[test]
+    fn test_parse_config_path() {
+        assert_eq!(
+            parse_config_path("/tmp/waker.json"),
+            Ok(PathBuf::from("/tmp/waker.json"))
+        );
+        assert!(parse_config_path("/tmp/waker.conf").is_err());
+        assert!(parse_config_path("/tmp/waker").is_err());
+        assert!(parse_config_path("/tmp/waker.JSON").is_err());
+    }
+
+    #[test]
+    fn test_parse_repeat() {
+        assert_eq!(parse_repeat("3"), Ok(3));
+        assert_eq!(parse_repeat(&MAX_REPEAT.to_string()), Ok(MAX_REPEAT));
+        assert_eq!(
+            parse_repeat("1000000"),
+            Err("Repeat count 1000000 is above the maximum of 100".to_string())
+        );
+        assert!(parse_repeat("many").is_err());
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("500ms"), Ok(Duration::from_millis(500)));
+        assert_eq!(parse_duration("10s"), Ok(Duration::from_secs(10)));
+        assert_eq!(parse_duration("10"), Ok(Duration::from_secs(10)));
+        assert_eq!(parse_duration("2m"), Ok(Duration::from_secs(120)));
+        assert_eq!(parse_duration("2h"), Ok(Duration::from_secs(2 * 3600)));
+        assert!(parse_duration("2d").is_err());
+        assert!(parse_duration("s").is_err());
+
+        // minutes that overflow a u64 of seconds are an error instead of a panic
+        assert_eq!(
+            parse_duration("18446744073709551615m"),
+            Err("Duration is too long: 18446744073709551615m".into())
+        );
+        assert!(parse_duration("18446744073709551615s").is_ok());
+    }
+
+    #[test]
+    fn test_format_interval() {
+        assert_eq!(format_interval(Duration::from_millis(1500)), "1500ms");
+        assert_eq!(format_interval(Duration::from_secs(45)), "45s");
+        assert_eq!(format_interval(Duration::from_secs(90 * 60)), "90m");
+        assert_eq!(format_interval(Duration::from_secs(2 * 3600)), "2h");
+        assert_eq!(format_interval(Duration::ZERO), "0s");
+    }
+
+    #[test]
+    fn test_format_last_woken_with_reminder() {
+        let mut machine = Machine::new("nas", None);
+        machine.remind_after = Some(Duration::from_secs(3600));
+        assert_eq!(format_last_woken(&machine), "");
+
+        machine.last_woken = Some(unix_now() - 600);
+        assert_eq!(format_last_woken(&machine), "10m ago (remind after 1h)");
+
+        machine.remind_after = Some(Duration::from_secs(5 * 60));
+        assert_eq!(format_last_woken(&machine), "10m ago (reminder due)");
+
+        machine.remind_after = None;
+        assert_eq!(format_last_woken(&machine), "10m ago");
+    }
+
+    #[test]
+    fn test_parse_time_of_day() {
+        assert_eq!(parse_time_of_day("02:00"), Ok(2 * 3600));
+        assert_eq!(parse_time_of_day("14:30"), Ok(14 * 3600 + 30 * 60));
+        assert_eq!(parse_time_of_day("2am"), Ok(2 * 3600));
+        assert_eq!(parse_time_of_day("2:30 PM"), Ok(14 * 3600 + 30 * 60));
+        assert_eq!(parse_time_of_day("12am"), Ok(0));
+        assert_eq!(parse_time_of_day("12pm"), Ok(12 * 3600));
+        assert!(parse_time_of_day("24:00").is_err());
+        assert!(parse_time_of_day("13pm").is_err());
+        assert!(parse_time_of_day("02:60").is_err());
+        assert!(parse_time_of_day("soon").is_err());
+    }
+
+    #[test]
+    fn test_delay_until() {
+        let midnight = 1_700_006_400;
+        assert_eq!(
+            delay_until(2 * 3600, midnight),
+            Duration::from_secs(2 * 3600)
+        );
+        assert_eq!(delay_until(0, midnight), Duration::ZERO);
+
+        // times that already passed today are tomorrow
+        assert_eq!(
+            delay_until(3600, midnight + 2 * 3600),
+            Duration::from_secs(23 * 3600)
+        );
+    }
+
+    #[test]
+    fn test_score_machine() {
+        let machines: Vec<Machine> = ["server", "nas", "desktop", "laptop"]
+            .into_iter()
+            .map(|name| Machine::new(name, Mac([0x01, 0x23, 0x45, 0x67, 0x89, 0xAB])))
+            .collect();
+        let matching = |input: &str| -> Vec<&str> {
+            machines
+                .iter()
+                .filter(|m| score_machine(input, m).is_some())
+                .map(|m| m.name.as_str())
+                .collect()
+        };
+
+        assert_eq!(matching(""), ["server", "nas", "desktop", "laptop"]);
+        assert_eq!(matching("ser"), ["server"]);
+        assert_eq!(matching("DESK"), ["desktop"]);
+        assert!(matching("zzz").is_empty());
+
+        // a prefix outranks a name that is just as similar
+        let lap = Machine::new("lap", None);
+        assert!(score_machine("lap", &machines[3]) > score_machine("ap", &lap));
+    }
+
+    #[test]
+    fn test_parse_env_machines_invalid() {
+        assert!(parse_env_machines("nas").is_err());
+        assert!(parse_env_machines("nas=01:23:45").is_err());
+    }
+}
@@ -0,0 +1,167 @@
+// waker-cli
+// Copyright (C) 2025 DarkCeptor44
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Conversions from and to the config format of the older `wol` CLI, which saved `wol.json` with the MAC addresses as plain strings
+
+use crate::types::{Data, Machine};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use waker::{Mac, MacAddressError};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct WolData {
+    pub machines: Vec<WolMachine>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WolMachine {
+    pub name: String,
+
+    /// MAC address as it was typed, the `wol` CLI didn't validate it
+    pub mac: String,
+}
+
+impl TryFrom<WolMachine> for Machine {
+    type Error = MacAddressError;
+
+    fn try_from(machine: WolMachine) -> Result<Self, Self::Error> {
+        Ok(Machine::new(machine.name, Mac::from_str(&machine.mac)?))
+    }
+}
+
+impl TryFrom<WolData> for Data {
+    type Error = anyhow::Error;
+
+    fn try_from(data: WolData) -> Result<Self> {
+        let machines = data
+            .machines
+            .into_iter()
+            .map(|machine| {
+                let name = machine.name.clone();
+                Machine::try_from(machine)
+                    .with_context(|| format!("Invalid MAC address for machine {name}"))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Data {
+            machines,
+            last_mac: None,
+        })
+    }
+}
+
+/// Only keeps the name and MAC address, machines without a MAC address are skipped since the `wol` CLI can't wake them
+impl From<&Data> for WolData {
+    fn from(data: &Data) -> Self {
+        Self {
+            machines: data
+                .machines
+                .iter()
+                .filter_map(|machine| {
+                    machine.mac.map(|mac| WolMachine {
+                        name: machine.name.clone(),
+                        mac: mac.to_string(),
+                    })
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Parses a config file in either the current format or the one of the `wol` CLI
+pub fn parse_any_config(content: &str) -> Result<Data> {
+    match serde_json::from_str::<Data>(content) {
+        Ok(data) => Ok(data),
+        Err(e) => match serde_json::from_str::<WolData>(content) {
+            Ok(data) => Data::try_from(data),
+            Err(_) => Err(e.into()),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wol_data_into_data() {
+        let wol = WolData {
+            machines: vec![WolMachine {
+                name: "nas".into(),
+                mac: "01-23-45-67-89-AB".into(),
+            }],
+        };
+
+        let data = Data::try_from(wol).expect("Failed to convert wol config");
+        assert_eq!(
+            data.machines,
+            [Machine::new(
+                "nas",
+                Mac([0x01, 0x23, 0x45, 0x67, 0x89, 0xAB])
+            )]
+        );
+    }
+
+    #[test]
+    fn test_wol_data_invalid_mac() {
+        let wol = WolData {
+            machines: vec![WolMachine {
+                name: "nas".into(),
+                mac: "01:23:45".into(),
+            }],
+        };
+
+        let err = Data::try_from(wol).unwrap_err();
+        assert!(err.to_string().contains("nas"));
+        assert!(err.downcast_ref::<MacAddressError>().is_some());
+    }
+
+    #[test]
+    fn test_data_into_wol_data() {
+        let mut by_ip = Machine::new("desktop", None);
+        by_ip.ip = Some("192.168.1.10".parse().unwrap());
+        let mut nas = Machine::new("nas", Mac([0x01, 0x23, 0x45, 0x67, 0x89, 0xAB]));
+        nas.tags = vec!["storage".into()];
+
+        let data = Data {
+            machines: vec![nas, by_ip],
+            last_mac: None,
+        };
+
+        assert_eq!(
+            WolData::from(&data),
+            WolData {
+                machines: vec![WolMachine {
+                    name: "nas".into(),
+                    mac: "01:23:45:67:89:ab".into(),
+                }],
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_any_config() {
+        let data = parse_any_config(r#"{"machines":[{"name":"nas","mac":"01:23:45:67:89:AB"}]}"#)
+            .expect("Failed to parse config");
+        assert_eq!(data.machines.len(), 1);
+
+        let err = parse_any_config(r#"{"machines":[{"name":"nas","mac":"nope"}]}"#).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Invalid MAC address for machine nas"));
+    }
+}
@@ -0,0 +1,413 @@
+// waker-cli
+// Copyright (C) 2025 DarkCeptor44
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::{
+    env,
+    io::Write,
+    net::{TcpListener, UdpSocket},
+    process::{Command, Stdio},
+    time::Duration,
+};
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_unwritable_config_error() {
+    // nothing can be created in /proc, not even by root
+    let output = Command::new(env!("CARGO_BIN_EXE_wake"))
+        .args(["--config", "/proc/waker.json", "normalize"])
+        .output()
+        .expect("Failed to run wake");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(!output.status.success());
+    assert!(stderr.contains("Failed to write config file /proc/waker.json"));
+    assert!(stderr.contains("--config"));
+}
+
+#[test]
+fn test_config_must_be_json() {
+    let output = Command::new(env!("CARGO_BIN_EXE_wake"))
+        .args(["--config", "waker.conf", "list"])
+        .output()
+        .expect("Failed to run wake");
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("The config file must have the `.json` extension, got waker.conf"));
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_unwritable_config_warns_after_wake() {
+    let receiver = UdpSocket::bind("127.0.0.1:0").expect("Failed to bind receiving socket");
+    let bcast_addr = receiver.local_addr().expect("Failed to get local address");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_wake"))
+        .args(["--config", "/proc/waker.json"])
+        .args(["-n", "01:23:45:67:89:AB"])
+        .args(["-b", &bcast_addr.to_string(), "-B", "127.0.0.1"])
+        .output()
+        .expect("Failed to run wake");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    // the machine was woken so the command still succeeds
+    assert!(output.status.success(), "{output:?}");
+    assert!(stderr.contains("Warning: the wake wasn't remembered"));
+    assert!(stderr.contains("Failed to write config file /proc/waker.json"));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_wake"))
+        .args(["--config", "/proc/waker.json", "--quiet"])
+        .args(["-n", "01:23:45:67:89:AB"])
+        .args(["-b", &bcast_addr.to_string(), "-B", "127.0.0.1"])
+        .output()
+        .expect("Failed to run wake");
+
+    assert!(output.status.success(), "{output:?}");
+    assert!(output.stderr.is_empty(), "{output:?}");
+}
+
+#[test]
+fn test_wake_and_wait_for_machine() {
+    let receiver = UdpSocket::bind("127.0.0.1:0").expect("Failed to bind receiving socket");
+    let bcast_addr = receiver.local_addr().expect("Failed to get local address");
+    let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind listener");
+    let probe = listener.local_addr().expect("Failed to get local address");
+    let config = env::temp_dir().join(format!("waker-cli-test-{}.json", std::process::id()));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_wake"))
+        .args(["--config", &config.to_string_lossy()])
+        .args(["-n", "01:23:45:67:89:AB"])
+        .args(["-b", &bcast_addr.to_string(), "-B", "127.0.0.1"])
+        .args([
+            "--repeat",
+            "2",
+            "--wait",
+            "5s",
+            "--probe",
+            &probe.to_string(),
+        ])
+        .output()
+        .expect("Failed to run wake");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    std::fs::remove_file(&config).unwrap_or_default();
+
+    assert!(output.status.success(), "{output:?}");
+    assert!(stdout.contains(&format!("{probe} is up after")));
+
+    let mut buffer = [0u8; 102];
+    for _ in 0..2 {
+        receiver
+            .recv_from(&mut buffer)
+            .expect("Failed to receive magic packet");
+    }
+}
+
+#[test]
+fn test_quiet_wake_prints_nothing() {
+    let receiver = UdpSocket::bind("127.0.0.1:0").expect("Failed to bind receiving socket");
+    let bcast_addr = receiver.local_addr().expect("Failed to get local address");
+    let config = env::temp_dir().join(format!("waker-cli-quiet-{}.json", std::process::id()));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_wake"))
+        .args(["--config", &config.to_string_lossy(), "--quiet"])
+        .args(["-n", "01:23:45:67:89:AB"])
+        .args(["-b", &bcast_addr.to_string(), "-B", "127.0.0.1"])
+        .output()
+        .expect("Failed to run wake");
+
+    std::fs::remove_file(&config).unwrap_or_default();
+
+    assert!(output.status.success(), "{output:?}");
+    assert!(output.stdout.is_empty(), "{output:?}");
+
+    let mut buffer = [0u8; 102];
+    receiver
+        .recv_from(&mut buffer)
+        .expect("Failed to receive magic packet");
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_quiet_hides_warnings() {
+    // the IP of a non-loopback interface, connecting a UDP socket sends nothing
+    let probe = UdpSocket::bind("0.0.0.0:0").expect("Failed to bind probe socket");
+    let Some(local_ip) = probe
+        .connect("192.0.2.1:9")
+        .and_then(|()| probe.local_addr())
+        .ok()
+        .map(|addr| addr.ip())
+        .filter(|ip| !ip.is_loopback() && !ip.is_unspecified())
+    else {
+        // no network interface to send to
+        return;
+    };
+
+    let receiver = UdpSocket::bind((local_ip, 0)).expect("Failed to bind receiving socket");
+    let target = receiver.local_addr().expect("Failed to get local address");
+    let wake = |quiet: bool| {
+        let mut command = Command::new(env!("CARGO_BIN_EXE_wake"));
+        if quiet {
+            command.arg("--quiet");
+        }
+
+        // a loopback bind address with a non-loopback target makes `check_bind` warn, the host still receives it
+        command
+            .args(["--config", "/proc/waker.json"])
+            .args(["-n", "01:23:45:67:89:AB"])
+            .args(["-b", &target.to_string(), "-B", "127.0.0.1"])
+            .output()
+            .expect("Failed to run wake")
+    };
+
+    let output = wake(false);
+    assert!(output.status.success(), "{output:?}");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("is a loopback address"));
+
+    let output = wake(true);
+    assert!(output.status.success(), "{output:?}");
+    assert!(output.stderr.is_empty(), "{output:?}");
+}
+
+#[test]
+fn test_quiet_wake_of_unknown_machine_fails() {
+    let config = env::temp_dir().join(format!("waker-cli-unknown-{}.json", std::process::id()));
+    let wake = |name: &str| {
+        Command::new(env!("CARGO_BIN_EXE_wake"))
+            .args(["--config", &config.to_string_lossy(), "--quiet", name])
+            .output()
+            .expect("Failed to run wake")
+    };
+
+    let output = wake("zzzz");
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty(), "{output:?}");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("No machines found in config file"));
+
+    std::fs::write(
+        &config,
+        r#"{"machines":[{"name":"nas","mac":"01:23:45:67:89:AB"}]}"#,
+    )
+    .expect("Failed to write config file");
+    let output = wake("zzzz");
+    std::fs::remove_file(&config).unwrap_or_default();
+
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty(), "{output:?}");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("No machine found with name: zzzz"));
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_machine_bind_address_is_used() {
+    let receiver = UdpSocket::bind("127.0.0.1:0").expect("Failed to bind receiving socket");
+    let bcast_addr = receiver.local_addr().expect("Failed to get local address");
+    let config = env::temp_dir().join(format!("waker-cli-bind-{}.json", std::process::id()));
+
+    std::fs::write(
+        &config,
+        r#"{"machines":[{"name":"nas","mac":"01:23:45:67:89:AB","bind_addr":"127.0.0.2"}]}"#,
+    )
+    .expect("Failed to write config file");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_wake"))
+        .args(["--config", &config.to_string_lossy()])
+        .args(["nas", "-b", &bcast_addr.to_string()])
+        .output()
+        .expect("Failed to run wake");
+
+    std::fs::remove_file(&config).unwrap_or_default();
+
+    assert!(output.status.success(), "{output:?}");
+
+    let mut buffer = [0u8; 102];
+    let (_, from) = receiver
+        .recv_from(&mut buffer)
+        .expect("Failed to receive magic packet");
+    assert_eq!(from.ip().to_string(), "127.0.0.2");
+}
+
+#[test]
+fn test_wake_mac_from_stdin() {
+    let receiver = UdpSocket::bind("127.0.0.1:0").expect("Failed to bind receiving socket");
+    let bcast_addr = receiver.local_addr().expect("Failed to get local address");
+    let config = env::temp_dir().join(format!("waker-cli-stdin-{}.json", std::process::id()));
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_wake"))
+        .args(["--config", &config.to_string_lossy()])
+        .args(["-n", "-", "-b", &bcast_addr.to_string(), "-B", "127.0.0.1"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to run wake");
+    child
+        .stdin
+        .take()
+        .expect("Failed to open stdin")
+        .write_all(b"01:23:45:67:89:AB\n")
+        .expect("Failed to write to stdin");
+    let output = child.wait_with_output().expect("Failed to wait for wake");
+
+    std::fs::remove_file(&config).unwrap_or_default();
+
+    assert!(output.status.success(), "{output:?}");
+
+    let mut buffer = [0u8; 102];
+    receiver
+        .recv_from(&mut buffer)
+        .expect("Failed to receive magic packet");
+    assert_eq!(buffer[6..12], [0x01, 0x23, 0x45, 0x67, 0x89, 0xAB]);
+}
+
+#[test]
+fn test_wake_in_zero_seconds_wakes_immediately() {
+    let receiver = UdpSocket::bind("127.0.0.1:0").expect("Failed to bind receiving socket");
+    receiver
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .expect("Failed to set read timeout");
+    let bcast_addr = receiver.local_addr().expect("Failed to get local address");
+    let config = env::temp_dir().join(format!("waker-cli-in-{}.json", std::process::id()));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_wake"))
+        .args(["--config", &config.to_string_lossy()])
+        .args(["-n", "01:23:45:67:89:AB", "--in", "0s"])
+        .args(["-b", &bcast_addr.to_string(), "-B", "127.0.0.1"])
+        .output()
+        .expect("Failed to run wake");
+
+    std::fs::remove_file(&config).unwrap_or_default();
+
+    assert!(output.status.success(), "{output:?}");
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("Waiting"));
+
+    let mut buffer = [0u8; 102];
+    receiver
+        .recv_from(&mut buffer)
+        .expect("Failed to receive magic packet");
+}
+
+#[test]
+fn test_wake_plan() {
+    let receiver = UdpSocket::bind("127.0.0.1:0").expect("Failed to bind receiving socket");
+    receiver
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .expect("Failed to set read timeout");
+    let bcast_addr = receiver.local_addr().expect("Failed to get local address");
+    let config = env::temp_dir().join(format!("waker-cli-plan-{}.json", std::process::id()));
+    let plan = env::temp_dir().join(format!("waker-cli-plan-{}.plan.json", std::process::id()));
+
+    std::fs::write(
+        &config,
+        r#"{"machines":[{"name":"nas","mac":"01:23:45:67:89:AB"}]}"#,
+    )
+    .expect("Failed to write config file");
+    std::fs::write(
+        &plan,
+        format!(
+            r#"{{"targets":["NAS","aa:bb:cc:dd:ee:ff"],"broadcast_addr":"{bcast_addr}","bind_addr":"127.0.0.1:0","repeat":2,"delay":"10ms"}}"#
+        ),
+    )
+    .expect("Failed to write plan file");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_wake"))
+        .args(["--config", &config.to_string_lossy()])
+        .args(["plan", &plan.to_string_lossy()])
+        .output()
+        .expect("Failed to run wake");
+
+    std::fs::remove_file(&config).unwrap_or_default();
+    std::fs::remove_file(&plan).unwrap_or_default();
+
+    assert!(output.status.success(), "{output:?}");
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Woke up 2 of 2 machines"));
+
+    let mut buffer = [0u8; 102];
+    let mut macs = Vec::new();
+    for _ in 0..4 {
+        receiver
+            .recv_from(&mut buffer)
+            .expect("Failed to receive magic packet");
+        macs.push(buffer[6..12].to_vec());
+    }
+    assert_eq!(
+        macs,
+        [
+            [0x01, 0x23, 0x45, 0x67, 0x89, 0xAB],
+            [0x01, 0x23, 0x45, 0x67, 0x89, 0xAB],
+            [0xAA, 0xBB, 0xCC, 0xDD, 0xEE, 0xFF],
+            [0xAA, 0xBB, 0xCC, 0xDD, 0xEE, 0xFF],
+        ]
+    );
+}
+
+#[test]
+fn test_wake_plan_unknown_machine() {
+    let config = env::temp_dir().join(format!(
+        "waker-cli-plan-unknown-{}.json",
+        std::process::id()
+    ));
+    let plan = env::temp_dir().join(format!(
+        "waker-cli-plan-unknown-{}.plan.json",
+        std::process::id()
+    ));
+
+    std::fs::write(&plan, r#"{"targets":["nas"]}"#).expect("Failed to write plan file");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_wake"))
+        .args(["--config", &config.to_string_lossy()])
+        .args(["plan", &plan.to_string_lossy()])
+        .output()
+        .expect("Failed to run wake");
+
+    std::fs::remove_file(&config).unwrap_or_default();
+    std::fs::remove_file(&plan).unwrap_or_default();
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("No machine found with name: nas"));
+}
+
+#[test]
+fn test_info_prints_version() {
+    let output = Command::new(env!("CARGO_BIN_EXE_wake"))
+        .arg("info")
+        .output()
+        .expect("Failed to run wake");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success(), "{output:?}");
+    assert!(stdout.contains(env!("CARGO_PKG_VERSION")));
+    assert!(stdout.contains("Commit: "));
+    assert!(stdout.contains("Target: "));
+
+    let features: Vec<&str> = [
+        ("log", cfg!(feature = "log")),
+        ("oui-db", cfg!(feature = "oui-db")),
+        ("socks", cfg!(feature = "socks")),
+    ]
+    .into_iter()
+    .filter_map(|(name, enabled)| enabled.then_some(name))
+    .collect();
+    let features = if features.is_empty() {
+        "none".to_string()
+    } else {
+        features.join(",")
+    };
+    assert!(
+        stdout.contains(&format!("Features: {features}\n")),
+        "{stdout}"
+    );
+}
@@ -19,6 +19,7 @@ use divan::black_box;
 use std::str::FromStr;
 use waker::Mac;
 
+const MAC_BYTES: [u8; 6] = [0x01, 0x23, 0x45, 0x67, 0x89, 0xAB];
 const MAC_STRING: &str = "01:23:45:67:89:AB";
 
 fn main() {
@@ -40,3 +41,23 @@ fn hex_val() {
 fn u8_from_str_radix() {
     black_box(u8::from_str_radix(black_box("0"), 16).expect("Failed to create MAC"));
 }
+
+#[divan::bench]
+fn mac_to_string() {
+    black_box(black_box(Mac(MAC_BYTES)).to_string());
+}
+
+#[divan::bench]
+fn mac_to_string_write() {
+    let mac = black_box(Mac(MAC_BYTES));
+    black_box(format!(
+        "{:02x}:{:02x}:{:02x}:{:02x}:{:02x}:{:02x}",
+        mac.0[0], mac.0[1], mac.0[2], mac.0[3], mac.0[4], mac.0[5]
+    ));
+}
+
+#[divan::bench]
+fn mac_to_str_buf() {
+    let mut buf = [0u8; 17];
+    black_box(black_box(Mac(MAC_BYTES)).to_str_buf(&mut buf));
+}
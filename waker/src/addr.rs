@@ -0,0 +1,267 @@
+// Copyright (C) 2025 DarkCeptor44
+//
+// This file is part of waker.
+//
+// waker is free software: you can redistribute it and/or modify
+// it under theterms of the GNU Lesser General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// waker is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Lesser General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public License
+// along with waker.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Helpers for classifying the addresses magic packets are sent to
+
+use crate::WakeError;
+use std::{
+    fmt,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+};
+
+/// The kind of broadcast an address represents
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BroadcastKind {
+    /// The limited broadcast address `255.255.255.255`, it only reaches the local network segment and routers never forward it
+    Limited,
+
+    /// A directed (subnet) broadcast address like `192.168.1.255`, routers can forward it to the target subnet if they are configured to
+    Directed,
+
+    /// Not a broadcast address, like a unicast or multicast address
+    NotBroadcast,
+}
+
+impl fmt::Display for BroadcastKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Limited => "limited broadcast",
+            Self::Directed => "directed broadcast",
+            Self::NotBroadcast => "non-broadcast",
+        })
+    }
+}
+
+/// Estimates the kind of broadcast an address represents
+///
+/// The netmask of the target network isn't known so any IPv4 address ending in `.255` is assumed to be a directed broadcast, which is what it is on the common `/24` networks.
+///
+/// ## Arguments
+///
+/// * `addr` - The IP address to classify
+///
+/// ## Examples
+///
+/// ```rust
+/// use std::net::Ipv4Addr;
+/// use waker::addr::{broadcast_kind, BroadcastKind};
+///
+/// assert_eq!(broadcast_kind(Ipv4Addr::BROADCAST), BroadcastKind::Limited);
+/// assert_eq!(broadcast_kind(Ipv4Addr::new(192, 168, 1, 255)), BroadcastKind::Directed);
+///
+/// let addr = Ipv4Addr::new(192, 168, 1, 255);
+/// println!("sending {} to {addr}", broadcast_kind(addr));
+/// ```
+#[must_use]
+pub fn broadcast_kind<A>(addr: A) -> BroadcastKind
+where
+    A: Into<IpAddr>,
+{
+    match addr.into() {
+        IpAddr::V4(ip) if ip.is_broadcast() => BroadcastKind::Limited,
+        IpAddr::V4(ip) if ip.octets()[3] == 255 && !ip.is_multicast() => BroadcastKind::Directed,
+        _ => BroadcastKind::NotBroadcast,
+    }
+}
+
+/// Returns `true` if the address is the limited broadcast address `255.255.255.255`
+///
+/// A limited broadcast won't cross a router, use a directed broadcast to reach another subnet.
+///
+/// ## Arguments
+///
+/// * `addr` - The IP address to classify
+#[must_use]
+pub fn is_limited_broadcast<A>(addr: A) -> bool
+where
+    A: Into<IpAddr>,
+{
+    broadcast_kind(addr) == BroadcastKind::Limited
+}
+
+/// Returns `true` if the address looks like a directed (subnet) broadcast address, see [`broadcast_kind`] for how it is estimated
+///
+/// ## Arguments
+///
+/// * `addr` - The IP address to classify
+#[must_use]
+pub fn is_directed_broadcast<A>(addr: A) -> bool
+where
+    A: Into<IpAddr>,
+{
+    broadcast_kind(addr) == BroadcastKind::Directed
+}
+
+/// Computes the directed broadcast address of the interface with the default route, on port `9`
+///
+/// On networks with several subnets this is cleaner than the limited broadcast `255.255.255.255`, see [`WakeOptions::default_broadcast`](crate::WakeOptions::default_broadcast) to use it with a fallback.
+///
+/// The routing table is read from `/proc/net/route` so this is only supported on Linux.
+///
+/// ## Errors
+///
+/// Returns [`WakeError::NoDefaultBroadcast`] if the routing table can't be read or has no default route with a matching subnet
+///
+/// ## Examples
+///
+/// ```rust
+/// use waker::addr::default_broadcast_addr;
+///
+/// match default_broadcast_addr() {
+///     Ok(addr) => println!("broadcasting to {addr}"),
+///     Err(e) => eprintln!("{e}"),
+/// }
+/// ```
+pub fn default_broadcast_addr() -> Result<SocketAddr, WakeError> {
+    let table = read_route_table()?;
+    let broadcast = route_table_broadcast(&table).ok_or_else(|| {
+        WakeError::NoDefaultBroadcast("no default route with a directly connected subnet".into())
+    })?;
+
+    Ok(SocketAddr::new(broadcast.into(), 9))
+}
+
+#[cfg(target_os = "linux")]
+fn read_route_table() -> Result<String, WakeError> {
+    std::fs::read_to_string("/proc/net/route")
+        .map_err(|e| WakeError::NoDefaultBroadcast(format!("failed to read /proc/net/route: {e}")))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_route_table() -> Result<String, WakeError> {
+    Err(WakeError::NoDefaultBroadcast(
+        "reading the routing table is only supported on Linux".into(),
+    ))
+}
+
+/// A route from `/proc/net/route`
+struct Route<'a> {
+    iface: &'a str,
+    destination: Ipv4Addr,
+    gateway: Ipv4Addr,
+    mask: Ipv4Addr,
+    metric: u32,
+}
+
+/// Finds the broadcast address of the directly connected subnet the default gateway is on, from the contents of `/proc/net/route`
+///
+/// This is what [`default_broadcast_addr`] uses, it's public so other sources of the routing table can be used too. Host routes like a `/32` route to the gateway, which DHCP and cloud providers often add, have no broadcast address so they are skipped.
+///
+/// ## Arguments
+///
+/// * `table` - The routing table in the format of `/proc/net/route`, including the header line
+///
+/// ## Returns
+///
+/// The broadcast address, or [`None`] if there is no default route or no connected subnet containing its gateway
+///
+/// ## Examples
+///
+/// ```rust
+/// use std::net::Ipv4Addr;
+/// use waker::addr::route_table_broadcast;
+///
+/// let table = "Iface\tDestination\tGateway\tFlags\tRefCnt\tUse\tMetric\tMask\tMTU\tWindow\tIRTT
+/// eth0\t00000000\t0101A8C0\t0003\t0\t0\t100\t00000000\t0\t0\t0
+/// eth0\t0001A8C0\t00000000\t0001\t0\t0\t100\t00FFFFFF\t0\t0\t0";
+///
+/// # if cfg!(target_endian = "little") {
+/// assert_eq!(route_table_broadcast(table), Some(Ipv4Addr::new(192, 168, 1, 255)));
+/// # }
+/// ```
+#[must_use]
+pub fn route_table_broadcast(table: &str) -> Option<Ipv4Addr> {
+    let routes: Vec<Route<'_>> = table.lines().skip(1).filter_map(parse_route).collect();
+    let default = routes
+        .iter()
+        .filter(|r| r.destination.is_unspecified() && r.mask.is_unspecified())
+        .min_by_key(|r| r.metric)?;
+    let gateway = u32::from(default.gateway);
+
+    routes
+        .iter()
+        .filter(|r| r.iface == default.iface && r.gateway.is_unspecified())
+        // `/31` and `/32` routes have no broadcast address
+        .filter(|r| !r.mask.is_unspecified() && u32::from(r.mask).count_ones() <= 30)
+        // a default route without a gateway goes straight out of the interface, so any of its subnets will do
+        .filter(|r| {
+            default.gateway.is_unspecified()
+                || gateway & u32::from(r.mask) == u32::from(r.destination)
+        })
+        .max_by_key(|r| u32::from(r.mask))
+        .map(|r| Ipv4Addr::from(u32::from(r.destination) | !u32::from(r.mask)))
+}
+
+/// Parses a line of `/proc/net/route`, the addresses are hexadecimal in host byte order
+fn parse_route(line: &str) -> Option<Route<'_>> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    let addr = |field: &str| {
+        u32::from_str_radix(field, 16)
+            .ok()
+            .map(|n| Ipv4Addr::from(n.to_ne_bytes()))
+    };
+
+    Some(Route {
+        iface: fields.first()?,
+        destination: addr(fields.get(1)?)?,
+        gateway: addr(fields.get(2)?)?,
+        metric: fields.get(6)?.parse().ok()?,
+        mask: addr(fields.get(7)?)?,
+    })
+}
+
+/// Looks up the MAC address of an IPv4 address in the ARP cache, see [`Mac::from_arp`](crate::Mac::from_arp)
+pub(crate) fn arp_lookup(ip: IpAddr) -> Result<crate::Mac, WakeError> {
+    let error = |reason: &str| WakeError::ArpLookup {
+        ip,
+        reason: reason.into(),
+    };
+
+    let IpAddr::V4(ip4) = ip else {
+        return Err(error(
+            "IPv6 addresses use neighbor discovery instead of ARP",
+        ));
+    };
+
+    let table = read_arp_table().map_err(|e| error(&e))?;
+    arp_table_mac(&table, ip4).ok_or_else(|| error("not in the cache, try reaching it first"))
+}
+
+#[cfg(target_os = "linux")]
+fn read_arp_table() -> Result<String, String> {
+    std::fs::read_to_string("/proc/net/arp")
+        .map_err(|e| format!("failed to read /proc/net/arp: {e}"))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_arp_table() -> Result<String, String> {
+    Err("reading the ARP cache is only supported on Linux".into())
+}
+
+/// Finds the MAC address of a complete entry for the IP in the content of `/proc/net/arp`
+fn arp_table_mac(table: &str, ip: Ipv4Addr) -> Option<crate::Mac> {
+    table.lines().skip(1).find_map(|line| {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let complete = fields.get(2).is_some_and(|flags| *flags != "0x0");
+
+        if complete && fields.first()?.parse::<Ipv4Addr>().ok()? == ip {
+            fields.get(3)?.parse().ok()
+        } else {
+            None
+        }
+    })
+}
@@ -0,0 +1,122 @@
+// Copyright (C) 2025 DarkCeptor44
+//
+// This file is part of waker.
+//
+// waker is free software: you can redistribute it and/or modify
+// it under theterms of the GNU Lesser General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// waker is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Lesser General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public License
+// along with waker.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Reading machines from DHCP lease files
+//!
+//! The dnsmasq lease file (usually `/var/lib/misc/dnsmasq.leases`) has one lease per line with the expiry time, MAC address, IP address, hostname and client ID separated by spaces:
+//!
+//! ```text
+//! 1735689600 01:23:45:67:89:ab 192.168.1.10 nas 01:01:23:45:67:89:ab
+//! ```
+//!
+//! Leases without a hostname have `*` instead, the IP address is used as the name for them.
+
+use crate::Mac;
+use anyhow::{Context, Result};
+use std::path::Path;
+
+/// The machines found in a DHCP lease file
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DhcpLeases {
+    /// The name and MAC address of every lease, in the order of the file
+    pub machines: Vec<(String, Mac)>,
+
+    /// How many lines were skipped because they aren't valid leases
+    pub skipped: usize,
+}
+
+/// Reads the machines from a dnsmasq lease file
+///
+/// ## Arguments
+///
+/// * `path` - The path of the lease file
+///
+/// ## Errors
+///
+/// Returns an error if the file can't be read, malformed lines are skipped and counted in [`DhcpLeases::skipped`] instead
+///
+/// ## Examples
+///
+/// ```rust,no_run
+/// use waker::{dhcp::parse_dhcp_leases, wake_device, WakeTarget};
+///
+/// let leases = parse_dhcp_leases("/var/lib/misc/dnsmasq.leases").unwrap();
+///
+/// for (name, mac) in leases.machines {
+///     println!("Waking up {name}");
+///     wake_device(WakeTarget::new(mac).wake_options()).unwrap();
+/// }
+/// ```
+pub fn parse_dhcp_leases<P>(path: P) -> Result<DhcpLeases>
+where
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read lease file {}", path.display()))?;
+
+    Ok(parse_leases(&content))
+}
+
+/// Parses the content of a dnsmasq lease file, see [`parse_dhcp_leases`]
+///
+/// ## Arguments
+///
+/// * `content` - The content of the lease file
+///
+/// ## Examples
+///
+/// ```rust
+/// use waker::{dhcp::parse_leases, Mac};
+///
+/// let leases = parse_leases("1735689600 01:23:45:67:89:ab 192.168.1.10 nas *\nnot a lease\n");
+///
+/// assert_eq!(leases.machines, vec![("nas".to_string(), Mac([0x01, 0x23, 0x45, 0x67, 0x89, 0xAB]))]);
+/// assert_eq!(leases.skipped, 1);
+/// ```
+#[must_use]
+pub fn parse_leases(content: &str) -> DhcpLeases {
+    let mut leases = DhcpLeases::default();
+
+    // the `duid` line holds the server's DHCPv6 identifier, it isn't a lease
+    for line in content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with("duid "))
+    {
+        match parse_lease(line) {
+            Some(machine) => leases.machines.push(machine),
+            None => leases.skipped += 1,
+        }
+    }
+
+    leases
+}
+
+/// Parses a single lease line into the hostname, or IP without one, and MAC address
+fn parse_lease(line: &str) -> Option<(String, Mac)> {
+    let mut fields = line.split_whitespace();
+    fields.next()?.parse::<u64>().ok()?;
+    let mac: Mac = fields.next()?.parse().ok()?;
+    let ip = fields.next()?;
+    let name = match fields.next()? {
+        "*" => ip,
+        hostname => hostname,
+    };
+
+    Some((name.to_string(), mac))
+}
@@ -15,20 +15,236 @@
 // You should have received a copy of the GNU Lesser General Public License
 // along with waker.  If not, see <https://www.gnu.org/licenses/>.
 
+use crate::{HwAddr, Mac};
+use std::{
+    convert::Infallible,
+    net::{IpAddr, SocketAddr},
+    time::Duration,
+};
 use thiserror::Error;
 
 /// Represents errors that can occur when working with MAC addresses
+///
+/// The enum is `#[non_exhaustive]` so new errors can be added without a major release, matches on it need a wildcard arm.
 #[derive(Debug, Error, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum MacAddressError {
     /// This happens when the MAC address byte cannot be parsed as a hexadecimal number
     #[error("Invalid byte in MAC address: {0}")]
     InvalidByteInMac(String),
 
+    /// This happens when a character in a MAC address string is neither a hexadecimal digit nor a separator where one is expected
+    #[error("Invalid character '{found}' at index {index} in MAC address")]
+    InvalidCharAt {
+        /// The byte index of the character in the input string
+        index: usize,
+
+        /// The invalid character
+        found: char,
+    },
+
     /// This happens when the MAC address string is not 6 bytes long or has an invalid format
     #[error("Invalid MAC address: {0}")]
     InvalidMacAddress(String),
 
+    /// This happens when a config line isn't a name and a MAC address separated by `=` or `,`
+    #[error("Expected `name=mac` but got: {0}")]
+    InvalidMachineLine(String),
+
     /// This happens when the MAC address byte slice is not 6 bytes long
     #[error("Invalid MAC address length: expected 6 bytes, got {0}")]
     InvalidLength(usize),
+
+    /// This happens when a unicast MAC address is required but the MAC address is a multicast or broadcast address
+    #[error("MAC address is not a unicast address: {0}")]
+    NotUnicast(Mac),
+
+    /// This happens when an EUI-64 identifier is used where a 48-bit MAC address is required, like when creating a magic packet
+    #[error("{0} is an EUI-64 identifier, a 48-bit MAC address is required")]
+    Eui64NotSupported(HwAddr),
+}
+
+impl From<Infallible> for MacAddressError {
+    fn from(value: Infallible) -> Self {
+        match value {}
+    }
+}
+
+impl MacAddressError {
+    /// Returns a stable, machine-readable code for the error, like `invalid_mac`
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use waker::MacAddressError;
+    ///
+    /// assert_eq!(MacAddressError::InvalidLength(5).code(), "invalid_length");
+    /// ```
+    #[must_use]
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::InvalidByteInMac(_) => "invalid_byte",
+            Self::InvalidCharAt { .. } => "invalid_char",
+            Self::InvalidMacAddress(_) => "invalid_mac",
+            Self::InvalidMachineLine(_) => "invalid_machine_line",
+            Self::InvalidLength(_) => "invalid_length",
+            Self::NotUnicast(_) => "not_unicast",
+            Self::Eui64NotSupported(_) => "eui64_not_supported",
+        }
+    }
+}
+
+/// Serializes as `{"code": ..., "message": ...}` so API clients get a stable code next to the message
+#[cfg(feature = "serde")]
+impl serde::Serialize for MacAddressError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serialize_error(serializer, self.code(), self)
+    }
+}
+
+/// Represents errors that can occur when sending a magic packet
+///
+/// The enum is `#[non_exhaustive]` so new errors can be added without a major release, matches on it need a wildcard arm.
+#[derive(Debug, Error, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum WakeError {
+    /// This happens when an address can't be parsed or resolved to a socket address
+    #[error("Invalid address: {0}")]
+    InvalidAddress(String),
+
+    /// This happens when the socket is bound to a loopback address while the broadcast address is not a loopback address, so the packet never reaches the network
+    #[error("Bind address {bind} is a loopback address but broadcast address {broadcast} is not, the magic packet will not leave this host")]
+    LoopbackBind {
+        /// The address the socket is bound to
+        bind: SocketAddr,
+
+        /// The address the magic packet is sent to
+        broadcast: SocketAddr,
+    },
+
+    /// This happens when a `wol://` URL can't be parsed into wake options
+    #[error("Invalid URL {0}")]
+    InvalidUrl(String),
+
+    /// This happens when bytes that should be a magic packet don't have its structure
+    #[error("Invalid magic packet: {0}")]
+    InvalidPacket(String),
+
+    /// This happens when one of the 16 MAC address repetitions of a magic packet is missing or doesn't match the first one, the repetitions are numbered from `1`
+    #[error("Invalid magic packet: MAC address repetition {0} of 16 is missing or doesn't match the first one")]
+    MismatchedRepetition(usize),
+
+    /// This happens when no magic packet arrives before the receive timeout
+    #[error("Timed out after {0:?} waiting for a magic packet")]
+    ReceiveTimeout(Duration),
+
+    /// This happens when a host doesn't accept a connection before the wait timeout, usually because it didn't wake up
+    #[error("{addr} did not become reachable within {timeout:?}")]
+    Unreachable {
+        /// The address that was polled
+        addr: SocketAddr,
+
+        /// How long it was polled for
+        timeout: Duration,
+    },
+
+    /// This happens when the directed broadcast address of the default interface can't be determined
+    #[error("Failed to find the default broadcast address: {0}")]
+    NoDefaultBroadcast(String),
+
+    /// This happens when the MAC address of an IP address can't be found in the ARP cache
+    #[error("Failed to find the MAC address of {ip} in the ARP cache: {reason}")]
+    ArpLookup {
+        /// The IP address that was looked up
+        ip: IpAddr,
+
+        /// Why it wasn't found
+        reason: String,
+    },
+
+    /// This happens when a SOCKS5 proxy replies with something other than a successful UDP association
+    #[error("Invalid proxy reply: {0}")]
+    InvalidProxyReply(String),
+
+    /// This happens when the repeat count is above the cap set with [`WakeOptions::max_repeat`](crate::WakeOptions::max_repeat)
+    #[error("Repeat count {count} is above the maximum of {max}")]
+    RepeatTooLarge {
+        /// The repeat count that was set
+        count: usize,
+
+        /// The maximum repeat count
+        max: usize,
+    },
+
+    /// This happens when a relay message can't be decoded
+    #[error("Invalid relay message: {0}")]
+    InvalidRelayMessage(String),
+
+    /// This happens when a relay is asked to send a magic packet to an address that isn't a broadcast address or in its allowlist
+    #[error("Relay target {0} is not a broadcast address or allowed")]
+    RelayTargetNotAllowed(SocketAddr),
+
+    /// This happens when the options add bytes that a relay would reject, like a trailer or a second SecureOn password, see [`relay::decode`](crate::relay::decode)
+    #[error("Packet can't be sent through a relay: {0}")]
+    UnrelayablePacket(String),
+}
+
+impl WakeError {
+    /// Returns a stable, machine-readable code for the error, like `invalid_address`
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use waker::WakeError;
+    ///
+    /// assert_eq!(WakeError::InvalidUrl("wol://".into()).code(), "invalid_url");
+    /// ```
+    #[must_use]
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::InvalidAddress(_) => "invalid_address",
+            Self::LoopbackBind { .. } => "loopback_bind",
+            Self::InvalidUrl(_) => "invalid_url",
+            Self::InvalidPacket(_) => "invalid_packet",
+            Self::MismatchedRepetition(_) => "mismatched_repetition",
+            Self::ReceiveTimeout(_) => "receive_timeout",
+            Self::Unreachable { .. } => "unreachable",
+            Self::NoDefaultBroadcast(_) => "no_default_broadcast",
+            Self::ArpLookup { .. } => "arp_lookup",
+            Self::InvalidProxyReply(_) => "invalid_proxy_reply",
+            Self::RepeatTooLarge { .. } => "repeat_too_large",
+            Self::InvalidRelayMessage(_) => "invalid_relay_message",
+            Self::RelayTargetNotAllowed(_) => "relay_target_not_allowed",
+            Self::UnrelayablePacket(_) => "unrelayable_packet",
+        }
+    }
+}
+
+/// Serializes as `{"code": ..., "message": ...}` so API clients get a stable code next to the message
+#[cfg(feature = "serde")]
+impl serde::Serialize for WakeError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serialize_error(serializer, self.code(), self)
+    }
+}
+
+/// Serializes an error as a struct with its code and message
+#[cfg(feature = "serde")]
+fn serialize_error<S, E>(serializer: S, code: &str, error: &E) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+    E: std::fmt::Display,
+{
+    use serde::ser::SerializeStruct;
+
+    let mut state = serializer.serialize_struct("Error", 2)?;
+    state.serialize_field("code", code)?;
+    state.serialize_field("message", &error.to_string())?;
+    state.end()
 }
@@ -31,15 +31,18 @@
 //!
 //! ```toml
 //! [dependencies]
-//! waker = "1"
+//! waker = "2"
 //!
 //! # this also works
-//! waker = { version = "1", git = "https://github.com/DarkCeptor44/waker" }
+//! waker = { version = "2", git = "https://github.com/DarkCeptor44/waker" }
 //! ```
 //!
 //! ## Features
 //!
+//! - `log`: Enables logging the outgoing packets with the [`log`](https://crates.io/crates/log) crate, see [`WakeOptions::log_packet`].
+//! - `oui-db`: Embeds a table of NIC vendors to look up the manufacturer of a MAC address, see `Mac::vendor`.
 //! - `serde`: Enables serialization and deserialization of the [`Mac`] and [`MagicPacket`] types.
+//! - `socks`: Enables sending the packets through a SOCKS5 proxy, see `WakeOptions::proxy`.
 //!
 //! ## Usage
 //!
@@ -55,6 +58,8 @@
 //!
 //! The MAC address can be passed as either [`&str`](str), [`String`], a byte array of length 6 ([`[u8; 6]`](u8)) or a byte slice ([`&[u8]`](u8)). Currently the string MAC address must have its bytes separated but `:`, `.` or `-` are all supported as separators.
 //!
+//! If you need to handle EUI-64 (8-byte) identifiers too you can parse them into a [`HwAddr`], only its 48-bit variant can be used to create a magic packet.
+//!
 //! The magic packet can then be sent using [`wake_device`]:
 //!
 //! ```rust,no_run
@@ -82,6 +87,19 @@
 //! wake_device(WakeOptions::new(&packet).bind_address("127.0.0.1:0")).unwrap();
 //! ```
 //!
+//! Note that binding to a loopback address like in the example above means the packet can only reach the same host, you can use [`WakeOptions::check_bind`] to detect that or enable [`WakeOptions::strict`] to make [`wake_device`] fail instead:
+//!
+//! ```rust
+//! use waker::{create_magic_packet, wake_device, WakeOptions};
+//!
+//! let packet = create_magic_packet("01:23:45:67:89:AB").unwrap();
+//! assert!(wake_device(WakeOptions::new(&packet).bind_address("127.0.0.1:0").strict(true)).is_err());
+//! ```
+//!
+//! If the machine is on a network the broadcast can't reach you can send the packet to a relay on that network with [`WakeOptions::relay`] instead, the [`relay`] module has the helpers for the receiving side.
+//!
+//! To wake machines handed out by dnsmasq the [`dhcp`] module reads their names and MAC addresses from its lease file.
+//!
 //! ## Audits
 //!
 //! No vulnerabilities found according to [cargo-audit](https://crates.io/crates/cargo-audit/)
@@ -127,14 +145,49 @@
 #![warn(clippy::pedantic, missing_debug_implementations, missing_docs)]
 #![allow(clippy::doc_markdown)]
 
+pub mod addr;
+pub mod dhcp;
 mod errors;
+#[cfg(feature = "oui-db")]
+mod oui;
+mod receiver;
+pub mod relay;
+#[cfg(feature = "socks")]
+mod socks;
 mod types;
 
 use anyhow::{Context, Result};
-use std::net::UdpSocket;
+use std::{
+    borrow::Cow,
+    hash::{BuildHasher, Hasher, RandomState},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, TcpStream, UdpSocket},
+    thread,
+    time::{Duration, Instant},
+};
 
-pub use errors::MacAddressError;
-pub use types::{AsMacBytes, Mac, MagicPacket, WakeOptions};
+pub use errors::{MacAddressError, WakeError};
+pub use receiver::MagicPacketReceiver;
+pub use types::{
+    AsMacBytes, HwAddr, Mac, MagicPacket, MagicPacketN, PacketLayout, PatternPacket, WakeOptions,
+    WakeStats, WakeTarget,
+};
+
+/// The synchronization stream every magic packet starts with, 6 bytes of `0xFF`
+///
+/// Receivers and custom packet builders can reference it instead of hardcoding the header, see [`MagicPacket::has_sync_stream`].
+pub const SYNC_STREAM: [u8; 6] = [0xFF; 6];
+
+/// The default cap on [`WakeOptions::repeat`], so a typo like `1000000` can't flood the network, see [`WakeOptions::max_repeat`]
+pub const MAX_REPEAT: usize = 100;
+
+/// The EtherType of Wake-on-LAN frames sent directly over Ethernet, see [`build_ethernet_frame`]
+pub const WOL_ETHERTYPE: u16 = 0x0842;
+
+/// The EtherType of ARP frames, see [`build_arp_request`]
+const ARP_ETHERTYPE: u16 = 0x0806;
+
+/// Minimum length of an Ethernet frame without the frame check sequence, shorter frames are padded with zeros
+const MIN_ETHERNET_FRAME_LEN: usize = 60;
 
 /// Creates a Wake-on-LAN magic packet for the given MAC address
 ///
@@ -196,19 +249,217 @@ where
 {
     let mac_bytes = mac_address.as_mac_bytes()?;
 
-    Ok(create_magic_packet_impl(mac_bytes))
+    Ok(MagicPacket::for_mac(Mac(mac_bytes)))
+}
+
+/// Creates a Wake-on-LAN magic packet for the given MAC address, rejecting MAC addresses that can't belong to a single machine
+///
+/// Waking a multicast or broadcast MAC address is meaningless so this is a safety rail for user-facing flows, [`create_magic_packet`] stays permissive.
+///
+/// ## Arguments
+///
+/// * `mac_address` - A type that can be converted into a [`Mac`] struct, see [`create_magic_packet`]
+///
+/// ## Returns
+///
+/// A [`Result`] containing the [`MagicPacket`] on success, on an error if the MAC address is invalid or not a unicast address
+///
+/// ## Errors
+///
+/// Returns [`MacAddressError::NotUnicast`] if the MAC address is a multicast or broadcast address, or another [`MacAddressError`] if the MAC address is invalid
+///
+/// ## Examples
+///
+/// ```rust
+/// use waker::create_magic_packet_strict;
+///
+/// assert!(create_magic_packet_strict("01:23:45:67:89:AB").is_err());
+/// assert!(create_magic_packet_strict("00:1B:21:12:34:56").is_ok());
+/// ```
+#[allow(clippy::needless_pass_by_value)]
+pub fn create_magic_packet_strict<T>(mac_address: T) -> Result<MagicPacket, MacAddressError>
+where
+    T: AsMacBytes,
+    MacAddressError: From<T::Error>,
+{
+    let mac = Mac(mac_address.as_mac_bytes()?);
+
+    if mac.is_multicast() {
+        return Err(MacAddressError::NotUnicast(mac));
+    }
+
+    Ok(MagicPacket::for_mac(mac))
+}
+
+/// Creates a Wake-on-LAN magic packet for the given MAC address as a fixed-size array, without allocating
+///
+/// The array can be sent directly with [`wake_device`] since [`WakeOptions`] can borrow the bytes.
+///
+/// ## Arguments
+///
+/// * `mac_address` - A type that can be converted into a [`Mac`] struct, see [`create_magic_packet`]
+///
+/// ## Returns
+///
+/// A [`Result`] containing the 102 bytes of the magic packet on success, on an error if the MAC address is invalid
+///
+/// ## Errors
+///
+/// Returns an error if the MAC address is invalid
+///
+/// ## Examples
+///
+/// ```rust,no_run
+/// use waker::{create_magic_packet_array, wake_device};
+///
+/// let packet = create_magic_packet_array("01:23:45:67:89:AB").unwrap();
+/// wake_device(&packet).unwrap();
+/// ```
+#[allow(clippy::needless_pass_by_value)]
+pub fn create_magic_packet_array<T>(mac_address: T) -> Result<[u8; 102], T::Error>
+where
+    T: AsMacBytes,
+{
+    let mac_bytes = mac_address.as_mac_bytes()?;
+
+    Ok(MagicPacketN::new(Mac(mac_bytes)).0)
+}
+
+/// Creates a packet with a custom wake pattern, for network interfaces configured to wake on a byte pattern instead of the magic packet
+///
+/// No validation is done on pattern packets, the bytes are sent as they are by [`wake_device`] so it's up to the caller to match what the interface expects.
+///
+/// ## Arguments
+///
+/// * `pattern` - The bytes of the wake pattern
+///
+/// ## Returns
+///
+/// A [`PatternPacket`] with a copy of the bytes
+///
+/// ## Examples
+///
+/// ```rust,no_run
+/// use waker::{create_pattern_packet, wake_device};
+///
+/// let packet = create_pattern_packet(&[0xDE, 0xAD, 0xBE, 0xEF]);
+/// wake_device(&packet).unwrap();
+/// ```
+#[must_use]
+pub fn create_pattern_packet(pattern: &[u8]) -> PatternPacket {
+    PatternPacket(pattern.to_vec())
+}
+
+/// Builds an Ethernet frame carrying the magic packet with the Wake-on-LAN EtherType (`0x0842`), for senders that use raw sockets
+///
+/// Sending the frame directly on the link reaches machines on segments where IP broadcasts are filtered. The frame check sequence is not included since network interfaces add it themselves, and frames shorter than 60 bytes are padded with zeros.
+///
+/// ## Arguments
+///
+/// * `src` - The MAC address of the interface the frame is sent from
+/// * `dst` - The destination MAC address, usually the broadcast address `ff:ff:ff:ff:ff:ff`
+/// * `payload` - The magic packet
+///
+/// ## Returns
+///
+/// The bytes of the frame: the destination and source MAC addresses, the EtherType and the magic packet
+///
+/// ## Examples
+///
+/// ```rust
+/// use waker::{build_ethernet_frame, create_magic_packet, Mac, WOL_ETHERTYPE};
+///
+/// let packet = create_magic_packet("01:23:45:67:89:AB").unwrap();
+/// let frame = build_ethernet_frame(Mac([0x00, 0x1B, 0x21, 0x12, 0x34, 0x56]), Mac([0xFF; 6]), &packet);
+///
+/// assert_eq!(frame.len(), 14 + 102);
+/// assert_eq!(frame[12..14], WOL_ETHERTYPE.to_be_bytes());
+/// ```
+#[must_use]
+pub fn build_ethernet_frame(src: Mac, dst: Mac, payload: &MagicPacket) -> Vec<u8> {
+    ethernet_frame(src, dst, WOL_ETHERTYPE, &payload.0)
+}
+
+/// Builds a broadcast Ethernet frame with an ARP request for the target, to prime the neighbor tables on the network after waking it
+///
+/// The target's MAC address is filled in so hosts that cache addresses from requests learn it, and a reply from the target updates the cache of the sender. Like [`build_ethernet_frame`] it has to be sent with a raw socket, which usually needs root or the `CAP_NET_RAW` capability.
+///
+/// ## Arguments
+///
+/// * `src` - The MAC address of the interface the frame is sent from
+/// * `src_ip` - The IPv4 address of the interface the frame is sent from
+/// * `target` - The MAC address of the machine that was woken up
+/// * `target_ip` - The IPv4 address of the machine that was woken up
+///
+/// ## Returns
+///
+/// The bytes of the frame, padded to the minimum Ethernet frame length
+///
+/// ## Examples
+///
+/// ```rust
+/// use std::net::Ipv4Addr;
+/// use waker::{build_arp_request, Mac};
+///
+/// let frame = build_arp_request(
+///     Mac([0x00, 0x1B, 0x21, 0x12, 0x34, 0x56]),
+///     Ipv4Addr::new(192, 168, 1, 2),
+///     Mac([0x01, 0x23, 0x45, 0x67, 0x89, 0xAB]),
+///     Ipv4Addr::new(192, 168, 1, 10),
+/// );
+///
+/// assert_eq!(frame[12..14], [0x08, 0x06]);
+/// ```
+#[must_use]
+pub fn build_arp_request(src: Mac, src_ip: Ipv4Addr, target: Mac, target_ip: Ipv4Addr) -> Vec<u8> {
+    let mut arp = Vec::with_capacity(28);
+    arp.extend_from_slice(&1u16.to_be_bytes()); // hardware type: Ethernet
+    arp.extend_from_slice(&0x0800u16.to_be_bytes()); // protocol type: IPv4
+    arp.extend_from_slice(&[6, 4]); // hardware and protocol address lengths
+    arp.extend_from_slice(&1u16.to_be_bytes()); // operation: request
+    arp.extend_from_slice(&src.0);
+    arp.extend_from_slice(&src_ip.octets());
+    arp.extend_from_slice(&target.0);
+    arp.extend_from_slice(&target_ip.octets());
+
+    ethernet_frame(src, Mac([0xFF; 6]), ARP_ETHERTYPE, &arp)
 }
 
-/// Creates a Wake-on-LAN magic packet from a 6-byte MAC address array
-fn create_magic_packet_impl(addr: [u8; 6]) -> MagicPacket {
-    let mut packet: Vec<u8> = vec![0xFF; 6];
-    packet.reserve(96);
+/// Builds an Ethernet frame without the frame check sequence, padding it to the minimum length
+fn ethernet_frame(src: Mac, dst: Mac, ethertype: u16, payload: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(MIN_ETHERNET_FRAME_LEN.max(14 + payload.len()));
+    frame.extend_from_slice(&dst.0);
+    frame.extend_from_slice(&src.0);
+    frame.extend_from_slice(&ethertype.to_be_bytes());
+    frame.extend_from_slice(payload);
 
-    for _ in 0..16 {
-        packet.extend_from_slice(&addr);
+    if frame.len() < MIN_ETHERNET_FRAME_LEN {
+        frame.resize(MIN_ETHERNET_FRAME_LEN, 0);
     }
 
-    MagicPacket(packet)
+    frame
+}
+
+/// Describes where each part of a magic packet is, so tools like hex viewers can annotate the bytes
+///
+/// ## Returns
+///
+/// A [`PacketLayout`] with the range of the sync header, the 16 MAC address slots and the optional SecureOn password
+///
+/// ## Examples
+///
+/// ```rust
+/// use waker::{create_magic_packet, magic_packet_layout};
+///
+/// let packet = create_magic_packet("01:23:45:67:89:AB").unwrap();
+/// let layout = magic_packet_layout();
+///
+/// assert_eq!(packet.0[layout.sync], [0xFF; 6]);
+/// assert_eq!(packet.0[layout.mac_slots[15].clone()], [0x01, 0x23, 0x45, 0x67, 0x89, 0xAB]);
+/// ```
+#[must_use]
+pub fn magic_packet_layout() -> PacketLayout {
+    PacketLayout::new()
 }
 
 /// Converts a character to a hexadecimal value.
@@ -235,6 +486,67 @@ pub fn hex_val(c: char) -> Result<u8, MacAddressError> {
     }
 }
 
+/// Splits a `name=mac` (or `name,mac`) line from a lightweight config format into the name and the parsed MAC address
+///
+/// Both sides are trimmed, so `nas = 01:23:45:67:89:AB` works too.
+///
+/// ## Arguments
+///
+/// * `line` - The line to parse
+///
+/// ## Errors
+///
+/// Returns [`MacAddressError::InvalidMachineLine`] if the line has no separator or an empty name, or the error from parsing the MAC address
+///
+/// ## Examples
+///
+/// ```rust
+/// use waker::{parse_machine_line, Mac};
+///
+/// let (name, mac) = parse_machine_line("nas=01:23:45:67:89:AB").unwrap();
+///
+/// assert_eq!(name, "nas");
+/// assert_eq!(mac, Mac([0x01, 0x23, 0x45, 0x67, 0x89, 0xAB]));
+/// assert!(parse_machine_line("nas").is_err());
+/// ```
+pub fn parse_machine_line(line: &str) -> Result<(String, Mac), MacAddressError> {
+    let Some((name, mac)) = line.split_once(['=', ',']) else {
+        return Err(MacAddressError::InvalidMachineLine(line.to_string()));
+    };
+
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(MacAddressError::InvalidMachineLine(line.to_string()));
+    }
+
+    Ok((name.to_string(), mac.parse()?))
+}
+
+/// Parses a SecureOn password for [`WakeOptions::secure_on`], which is written like a MAC address
+///
+/// ## Arguments
+///
+/// * `password` - The password in any of the formats [`Mac`] parses, like `11:22:33:44:55:66` or `11-22-33-44-55-66`
+///
+/// ## Errors
+///
+/// Returns the same errors as parsing a [`Mac`]
+///
+/// ## Examples
+///
+/// ```rust
+/// use waker::parse_secure_on_password;
+///
+/// assert_eq!(
+///     parse_secure_on_password("11:22:33:44:55:66").unwrap(),
+///     [0x11, 0x22, 0x33, 0x44, 0x55, 0x66]
+/// );
+/// assert!(parse_secure_on_password("11:22:33").is_err());
+/// ```
+pub fn parse_secure_on_password(password: &str) -> Result<[u8; 6], MacAddressError> {
+    Ok(password.parse::<Mac>()?.0)
+}
+
 /// Sends a Wake-on-LAN magic packet to a broadcast address for waking up a specific device
 ///
 /// ## Arguments
@@ -247,7 +559,15 @@ pub fn hex_val(c: char) -> Result<u8, MacAddressError> {
 ///
 /// ## Errors
 ///
-/// Returns an error if the UDP socket cannot be bound, if the broadcast option cannot be set, or if sending the packet fails
+/// Returns [`WakeError::InvalidAddress`] if the broadcast or bind address is invalid, or an error if the UDP socket cannot be bound, if the broadcast option cannot be set, or if sending the packet fails.
+///
+/// If [`WakeOptions::strict`] is enabled it also returns a [`WakeError`] when [`WakeOptions::check_bind`] fails. Otherwise the packet is sent anyway and the failure is only logged as a warning with the `log` feature, so callers that don't enable either should call [`WakeOptions::check_bind`] themselves to catch a loopback bind.
+///
+/// Returns [`WakeError::RepeatTooLarge`] if [`WakeOptions::repeat`] is above [`WakeOptions::max_repeat`], nothing is sent in that case.
+///
+/// Returns [`WakeError::UnrelayablePacket`] if a [`WakeOptions::relay`] is set along with a [`WakeOptions::trailer`], or with a [`WakeOptions::secure_on`] password on a packet that already has one, since the relay would drop the message.
+///
+/// With [`WakeOptions::also_unicast`] both packets are always sent and an error is returned if either send fails.
 ///
 /// ## Examples
 ///
@@ -283,22 +603,446 @@ pub fn wake_device<'a, O>(options: O) -> Result<()>
 where
     O: Into<WakeOptions<'a>>,
 {
-    wake_device_impl(options.into())
+    wake_device_impl(options.into()).map(|_| ())
 }
 
-/// Sends a Wake-on-LAN magic packet to a broadcast address for waking up a specific device
+/// Sends a Wake-on-LAN magic packet like [`wake_device`] and returns how long each phase took
+///
+/// This is meant for dashboards and diagnostics, a slow `bind_time` usually points to a slow name resolution or network interface.
+///
+/// ## Arguments
+///
+/// * `options` - A [`WakeOptions`] struct containing the magic packet, broadcast address, and bind address
+///
+/// ## Returns
+///
+/// A [`Result`] containing the [`WakeStats`] of the call
+///
+/// ## Errors
+///
+/// Returns the same errors as [`wake_device`]
+///
+/// ## Examples
+///
+/// ```rust,no_run
+/// use waker::{create_magic_packet, wake_device_with_stats};
+///
+/// let packet = create_magic_packet("01:23:45:67:89:AB").unwrap();
+/// let stats = wake_device_with_stats(&packet).unwrap();
+///
+/// println!("Bound in {:?}, sent in {:?}", stats.bind_time, stats.send_time);
+/// ```
+pub fn wake_device_with_stats<'a, O>(options: O) -> Result<WakeStats>
+where
+    O: Into<WakeOptions<'a>>,
+{
+    wake_device_impl(options.into()).map(|(_, stats)| stats)
+}
+
+/// Sends a Wake-on-LAN magic packet like [`wake_device`] and returns the local address the packet was sent from
+///
+/// When binding to port `0` the OS picks an ephemeral port, this reports the port it picked so it can be logged or allowed through a firewall.
+///
+/// ## Arguments
+///
+/// * `options` - A [`WakeOptions`] struct containing the magic packet, broadcast address, and bind address
+///
+/// ## Returns
+///
+/// A [`Result`] containing the address the UDP socket was bound to
+///
+/// ## Errors
+///
+/// Returns the same errors as [`wake_device`], or an error if the local address of the socket can't be read
+///
+/// ## Examples
+///
+/// ```rust,no_run
+/// use waker::{create_magic_packet, wake_device_local_addr};
+///
+/// let packet = create_magic_packet("01:23:45:67:89:AB").unwrap();
+/// let local_addr = wake_device_local_addr(&packet).unwrap();
+///
+/// println!("Sent magic packet from {local_addr}");
+/// ```
+pub fn wake_device_local_addr<'a, O>(options: O) -> Result<SocketAddr>
+where
+    O: Into<WakeOptions<'a>>,
+{
+    wake_device_impl(options.into()).map(|(local_addr, _)| local_addr)
+}
+
+/// Sends a Wake-on-LAN magic packet to a broadcast address for waking up a specific device, returning the local address of the socket and the timings
 #[allow(clippy::needless_pass_by_value)]
-fn wake_device_impl(options: WakeOptions) -> Result<()> {
-    let socket = UdpSocket::bind(&*options.bind_address).context("Failed to bind UDP socket")?;
+fn wake_device_impl(options: WakeOptions) -> Result<(SocketAddr, WakeStats)> {
+    check_repeat(&options)?;
+
+    let start = Instant::now();
+    let (socket, broadcast_addr) = open_socket(&options)?;
+    let local_addr = socket
+        .local_addr()
+        .context("Failed to get local address of UDP socket")?;
+    let bind_time = start.elapsed();
+
+    let packet = full_packet(&options.packet, &options);
+    let sender = Sender::new(socket, &options, local_addr)?;
+    let send_time = send_repeated(&sender, &options, &packet, broadcast_addr)?;
+
+    let stats = WakeStats {
+        bind_time,
+        send_time,
+        total: start.elapsed(),
+    };
+
+    Ok((local_addr, stats))
+}
+
+/// Fails with [`WakeError::RepeatTooLarge`] if the repeat count is above [`WakeOptions::max_repeat`]
+fn check_repeat(options: &WakeOptions) -> Result<(), WakeError> {
+    if options.repeat > options.max_repeat {
+        return Err(WakeError::RepeatTooLarge {
+            count: options.repeat,
+            max: options.max_repeat,
+        });
+    }
+
+    Ok(())
+}
+
+/// Appends the SecureOn password and the trailer of the options to the packet, if there are any
+fn full_packet<'p>(packet: &'p [u8], options: &WakeOptions) -> Cow<'p, [u8]> {
+    match (&options.pass, &options.trailer) {
+        (None, None) => Cow::Borrowed(packet),
+        (password, trailer) => Cow::Owned(
+            [
+                packet,
+                password.as_ref().map_or(&[][..], |p| p.as_slice()),
+                trailer.as_deref().unwrap_or_default(),
+            ]
+            .concat(),
+        ),
+    }
+}
+
+/// Sends the packet [`WakeOptions::repeat`] times, waiting the interval between sends, and returns the time spent sending
+fn send_repeated(
+    sender: &Sender,
+    options: &WakeOptions,
+    packet: &[u8],
+    broadcast_addr: SocketAddr,
+) -> Result<Duration> {
+    let mut send_time = Duration::ZERO;
+
+    for i in 0..options.repeat.max(1) {
+        if i > 0 && !options.dry_run {
+            thread::sleep(jittered(options.interval, options.jitter));
+        }
+
+        let send_start = Instant::now();
+        send_packet(sender, options, packet, broadcast_addr)?;
+        send_time += send_start.elapsed();
+    }
+
+    Ok(send_time)
+}
+
+/// The UDP socket the packets are sent from, optionally through a SOCKS5 proxy
+struct Sender {
+    socket: UdpSocket,
+
+    #[cfg(feature = "socks")]
+    proxy: Option<socks::UdpAssociate>,
+}
+
+impl Sender {
+    #[cfg_attr(
+        not(feature = "socks"),
+        allow(clippy::unnecessary_wraps, unused_variables)
+    )]
+    fn new(socket: UdpSocket, options: &WakeOptions, local_addr: SocketAddr) -> Result<Self> {
+        #[cfg(feature = "socks")]
+        let proxy = match &options.proxy {
+            // a dry run shouldn't talk to the proxy either, the address is still validated
+            Some(proxy) if options.dry_run => {
+                types::resolve_address(proxy)?;
+                None
+            }
+            Some(proxy) => Some(socks::UdpAssociate::connect(
+                types::resolve_address(proxy)?,
+                local_addr,
+            )?),
+            None => None,
+        };
+
+        Ok(Self {
+            socket,
+            #[cfg(feature = "socks")]
+            proxy,
+        })
+    }
+
+    fn send_to(&self, data: &[u8], addr: SocketAddr) -> std::io::Result<usize> {
+        #[cfg(feature = "socks")]
+        if let Some(proxy) = &self.proxy {
+            return proxy.send_to(&self.socket, data, addr);
+        }
+
+        self.socket.send_to(data, addr)
+    }
+}
+
+/// Sends the packet once, to the relay if there is one or to the broadcast and unicast addresses
+fn send_packet(
+    socket: &Sender,
+    options: &WakeOptions,
+    packet: &[u8],
+    broadcast_addr: SocketAddr,
+) -> Result<()> {
+    if let Some(relay) = &options.relay {
+        let relay_addr = types::resolve_address(relay)?;
+        log_packet(options, packet, relay_addr);
+        if options.dry_run {
+            return Ok(());
+        }
+
+        socket
+            .send_to(&relay::encode(packet, broadcast_addr), relay_addr)
+            .with_context(|| format!("Failed to send magic packet to relay {relay_addr}"))?;
+        options.notify_sent(packet, relay_addr);
+
+        return Ok(());
+    }
 
-    // TODO implement secure_on
+    log_packet(options, packet, broadcast_addr);
+    if options.dry_run {
+        return Ok(());
+    }
+
+    let broadcast_result = socket
+        .send_to(packet, broadcast_addr)
+        .context("Failed to send magic packet");
+    if broadcast_result.is_ok() {
+        options.notify_sent(packet, broadcast_addr);
+    }
 
-    socket
-        .set_broadcast(true)
-        .context("Failed to set socket to broadcast")?;
-    socket
-        .send_to(&options.packet.0, &*options.broadcast_address)
-        .context("Failed to send magic packet")?;
+    // the unicast packet is sent even if the broadcast failed since either one can wake the machine
+    if let Some(ip) = options.unicast {
+        let unicast_addr = SocketAddr::new(ip, broadcast_addr.port());
+        log_packet(options, packet, unicast_addr);
 
+        socket
+            .send_to(packet, unicast_addr)
+            .with_context(|| format!("Failed to send magic packet to {unicast_addr}"))?;
+        options.notify_sent(packet, unicast_addr);
+    }
+
+    broadcast_result?;
     Ok(())
 }
+
+/// Returns a random delay between `base` and `base + jitter`
+fn jittered(base: Duration, jitter: Duration) -> Duration {
+    if jitter.is_zero() {
+        return base;
+    }
+
+    // every RandomState is seeded with different random keys so hashing nothing is enough to get a random number
+    let random = RandomState::new().build_hasher().finish();
+    let max = u64::try_from(jitter.as_nanos()).unwrap_or(u64::MAX);
+
+    base + Duration::from_nanos(random % max.saturating_add(1))
+}
+
+/// Wakes up many machines over a single UDP socket, building and sending each magic packet lazily
+///
+/// This is meant for fleets of machines, like thousands of MAC addresses read from a file, the packets are never buffered and the socket is only set up once. Nothing is sent until the returned iterator is consumed.
+///
+/// The packet in `options` is ignored since every MAC address gets its own, every other option applies to each packet just like with [`wake_device`], including the repeat count, relay, proxy and [`WakeOptions::dry_run`].
+///
+/// ## Arguments
+///
+/// * `macs` - The MAC addresses of the machines to wake up
+/// * `options` - A [`WakeOptions`] struct containing the broadcast and bind addresses
+///
+/// ## Returns
+///
+/// An iterator that sends one magic packet per MAC address and yields the MAC address along with the result of sending its packet
+///
+/// ## Errors
+///
+/// Returns an error if the socket can't be set up or the repeat count is too large, for the same reasons as [`wake_device`]. Errors sending individual packets are yielded by the iterator instead.
+///
+/// ## Examples
+///
+/// ```rust,no_run
+/// use waker::{wake_stream, Mac, WakeOptions};
+///
+/// let macs = ["01:23:45:67:89:AB", "01:23:45:67:89:AC"]
+///     .into_iter()
+///     .filter_map(|s| s.parse::<Mac>().ok());
+///
+/// for (mac, result) in wake_stream(macs, &WakeOptions::new(Vec::new())).unwrap() {
+///     if let Err(e) = result {
+///         eprintln!("Failed to wake {mac}: {e}");
+///     }
+/// }
+/// ```
+pub fn wake_stream<'a, I>(
+    macs: I,
+    options: &'a WakeOptions<'a>,
+) -> Result<impl Iterator<Item = (Mac, Result<()>)> + 'a>
+where
+    I: IntoIterator<Item = Mac>,
+    I::IntoIter: 'a,
+{
+    check_repeat(options)?;
+
+    let (socket, broadcast_addr) = open_socket(options)?;
+    let local_addr = socket
+        .local_addr()
+        .context("Failed to get local address of UDP socket")?;
+    let sender = Sender::new(socket, options, local_addr)?;
+
+    Ok(macs.into_iter().map(move |mac| {
+        let packet = MagicPacket::for_mac(mac);
+        let result = send_repeated(
+            &sender,
+            options,
+            &full_packet(&packet.0, options),
+            broadcast_addr,
+        )
+        .map(|_| ());
+
+        (mac, result)
+    }))
+}
+
+/// Waits for a woken up machine to accept TCP connections, polling it until the timeout
+///
+/// Magic packets are fire-and-forget so this is the way to confirm a machine actually woke up, point it at a port the machine listens on like `22` for SSH.
+///
+/// ## Arguments
+///
+/// * `addr` - The address of the machine and a port it listens on (in `IP:PORT` format)
+/// * `timeout` - How long to keep polling
+///
+/// ## Returns
+///
+/// How long it took for the machine to become reachable
+///
+/// ## Errors
+///
+/// Returns [`WakeError::InvalidAddress`] if the address can't be resolved or [`WakeError::Unreachable`] if no connection succeeded before the timeout
+///
+/// ## Examples
+///
+/// ```rust,no_run
+/// use std::time::Duration;
+/// use waker::{create_magic_packet, wait_until_reachable, wake_device};
+///
+/// let packet = create_magic_packet("01:23:45:67:89:AB").unwrap();
+/// wake_device(&packet).unwrap();
+///
+/// let elapsed = wait_until_reachable("192.168.1.10:22", Duration::from_secs(60)).unwrap();
+/// println!("up after {elapsed:?}");
+/// ```
+pub fn wait_until_reachable(addr: &str, timeout: Duration) -> Result<Duration, WakeError> {
+    const ATTEMPT_TIMEOUT: Duration = Duration::from_secs(1);
+    const RETRY_DELAY: Duration = Duration::from_millis(200);
+
+    let addr = types::resolve_address(addr)?;
+    let start = Instant::now();
+
+    loop {
+        let remaining = timeout.saturating_sub(start.elapsed());
+        if remaining.is_zero() {
+            return Err(WakeError::Unreachable { addr, timeout });
+        }
+
+        if TcpStream::connect_timeout(&addr, remaining.min(ATTEMPT_TIMEOUT)).is_ok() {
+            return Ok(start.elapsed());
+        }
+
+        // refused connections fail immediately, don't hammer the machine while it boots
+        thread::sleep(RETRY_DELAY.min(timeout.saturating_sub(start.elapsed())));
+    }
+}
+
+/// Binds the UDP socket described by the options and resolves the broadcast address to send to
+fn open_socket(options: &WakeOptions) -> Result<(UdpSocket, SocketAddr)> {
+    if options.strict {
+        options.check_bind()?;
+    } else {
+        #[cfg(feature = "log")]
+        if let Err(e) = options.check_bind() {
+            log::warn!("{e}");
+        }
+    }
+
+    let broadcast_addr = options.resolved_broadcast()?;
+    let bind_addr = options.resolved_bind()?;
+
+    // the relay is what the socket sends to so it decides the family, and the relay does the broadcasting itself
+    if let Some(relay) = &options.relay {
+        options.check_relay_payload()?;
+
+        let relay_addr = types::resolve_address(relay)?;
+        let socket = UdpSocket::bind(bind_for_family(bind_addr, relay_addr))
+            .context("Failed to bind UDP socket")?;
+
+        return Ok((socket, broadcast_addr));
+    }
+
+    let socket = UdpSocket::bind(bind_for_family(bind_addr, broadcast_addr))
+        .context("Failed to bind UDP socket")?;
+
+    match broadcast_addr {
+        SocketAddr::V4(_) => socket
+            .set_broadcast(true)
+            .context("Failed to set socket to broadcast")?,
+
+        // IPv6 has no broadcast, sending to a multicast group like `ff02::1` doesn't require joining it
+        SocketAddr::V6(addr) if addr.ip().is_multicast() => socket
+            .set_multicast_loop_v6(true)
+            .context("Failed to enable multicast loopback")?,
+
+        SocketAddr::V6(_) => {}
+    }
+
+    Ok((socket, broadcast_addr))
+}
+
+/// Swaps an unspecified bind address like `0.0.0.0` for the one of the target's family, keeping the port, so the default bind address works for IPv6 targets too
+fn bind_for_family(bind: SocketAddr, target: SocketAddr) -> SocketAddr {
+    if !bind.ip().is_unspecified() || bind.is_ipv4() == target.is_ipv4() {
+        return bind;
+    }
+
+    let ip = if target.is_ipv4() {
+        IpAddr::V4(Ipv4Addr::UNSPECIFIED)
+    } else {
+        IpAddr::V6(Ipv6Addr::UNSPECIFIED)
+    };
+
+    SocketAddr::new(ip, bind.port())
+}
+
+/// Logs the target MAC address and length of a packet that is about to be sent to the destination, if [`WakeOptions::log_packet`] is enabled
+#[cfg_attr(not(feature = "log"), allow(unused_variables))]
+fn log_packet(options: &WakeOptions, packet: &[u8], destination: SocketAddr) {
+    #[cfg(feature = "log")]
+    if options.log_packet {
+        log::debug!(
+            "{} magic packet for {} ({} bytes) to {}",
+            if options.dry_run {
+                "Dry run, not sending"
+            } else {
+                "Sending"
+            },
+            types::packet_mac(packet)
+                .map_or_else(|| "unknown MAC".to_string(), |mac| mac.to_string()),
+            packet.len(),
+            destination
+        );
+    }
+}
@@ -0,0 +1,110 @@
+// Copyright (C) 2025 DarkCeptor44
+//
+// This file is part of waker.
+//
+// waker is free software: you can redistribute it and/or modify
+// it under theterms of the GNU Lesser General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// waker is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Lesser General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public License
+// along with waker.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Embedded table of NIC vendors used by `Mac::vendor`
+
+/// Common vendors of network interfaces by their OUI, sorted so it can be binary searched
+static VENDORS: &[([u8; 3], &str)] = &[
+    ([0x00, 0x00, 0x0C], "Cisco"),
+    ([0x00, 0x00, 0xF0], "Samsung"),
+    ([0x00, 0x02, 0xB3], "Intel"),
+    ([0x00, 0x02, 0xC9], "Mellanox"),
+    ([0x00, 0x03, 0x93], "Apple"),
+    ([0x00, 0x04, 0x0E], "AVM"),
+    ([0x00, 0x05, 0x5D], "D-Link"),
+    ([0x00, 0x05, 0x69], "VMware"),
+    ([0x00, 0x05, 0x85], "Juniper"),
+    ([0x00, 0x08, 0x9B], "QNAP"),
+    ([0x00, 0x09, 0x5B], "Netgear"),
+    ([0x00, 0x09, 0xBF], "Nintendo"),
+    ([0x00, 0x0A, 0x95], "Apple"),
+    ([0x00, 0x0B, 0xCD], "HP"),
+    ([0x00, 0x0C, 0x29], "VMware"),
+    ([0x00, 0x0D, 0x88], "D-Link"),
+    ([0x00, 0x0E, 0x0C], "Intel"),
+    ([0x00, 0x10, 0x18], "Broadcom"),
+    ([0x00, 0x11, 0x32], "Synology"),
+    ([0x00, 0x13, 0x20], "Intel"),
+    ([0x00, 0x14, 0x22], "Dell"),
+    ([0x00, 0x14, 0x6C], "Netgear"),
+    ([0x00, 0x15, 0x17], "Intel"),
+    ([0x00, 0x15, 0x5D], "Microsoft Hyper-V"),
+    ([0x00, 0x16, 0x3E], "Xen"),
+    ([0x00, 0x16, 0xCB], "Apple"),
+    ([0x00, 0x17, 0xF2], "Apple"),
+    ([0x00, 0x1B, 0x11], "D-Link"),
+    ([0x00, 0x1B, 0x21], "Intel"),
+    ([0x00, 0x1B, 0x2F], "Netgear"),
+    ([0x00, 0x1C, 0x14], "VMware"),
+    ([0x00, 0x1C, 0x42], "Parallels"),
+    ([0x00, 0x1C, 0xB3], "Apple"),
+    ([0x00, 0x1E, 0x67], "Intel"),
+    ([0x00, 0x1E, 0xC2], "Apple"),
+    ([0x00, 0x1F, 0xF3], "Apple"),
+    ([0x00, 0x21, 0x9B], "Dell"),
+    ([0x00, 0x24, 0x1D], "Gigabyte"),
+    ([0x00, 0x25, 0x00], "Apple"),
+    ([0x00, 0x25, 0x90], "Super Micro"),
+    ([0x00, 0x50, 0x56], "VMware"),
+    ([0x00, 0xD8, 0x61], "Micro-Star"),
+    ([0x00, 0xE0, 0x4C], "Realtek"),
+    ([0x00, 0xE0, 0xFC], "Huawei"),
+    ([0x04, 0x18, 0xD6], "Ubiquiti"),
+    ([0x04, 0xD4, 0xC4], "ASUSTek"),
+    ([0x08, 0x00, 0x27], "VirtualBox"),
+    ([0x0C, 0xC4, 0x7A], "Super Micro"),
+    ([0x18, 0x03, 0x73], "Dell"),
+    ([0x1C, 0x1B, 0x0D], "Gigabyte"),
+    ([0x1C, 0x7E, 0xE5], "D-Link"),
+    ([0x24, 0x0A, 0xC4], "Espressif"),
+    ([0x24, 0x5E, 0xBE], "QNAP"),
+    ([0x24, 0xA4, 0x3C], "Ubiquiti"),
+    ([0x28, 0xCD, 0xC1], "Raspberry Pi"),
+    ([0x28, 0xCF, 0xE9], "Apple"),
+    ([0x2C, 0x56, 0xDC], "ASUSTek"),
+    ([0x30, 0xAE, 0xA4], "Espressif"),
+    ([0x3C, 0x07, 0x54], "Apple"),
+    ([0x3C, 0x97, 0x0E], "Intel"),
+    ([0x3C, 0xD9, 0x2B], "HP"),
+    ([0x44, 0xD9, 0xE7], "Ubiquiti"),
+    ([0x4C, 0xCC, 0x6A], "Micro-Star"),
+    ([0x50, 0xC7, 0xBF], "TP-Link"),
+    ([0x78, 0x8A, 0x20], "Ubiquiti"),
+    ([0x80, 0x2A, 0xA8], "Ubiquiti"),
+    ([0xA0, 0x36, 0x9F], "Intel"),
+    ([0xA0, 0x40, 0xA0], "Netgear"),
+    ([0xA4, 0x5E, 0x60], "Apple"),
+    ([0xAC, 0x1F, 0x6B], "Super Micro"),
+    ([0xAC, 0x22, 0x0B], "ASUSTek"),
+    ([0xAC, 0xBC, 0x32], "Apple"),
+    ([0xB8, 0x27, 0xEB], "Raspberry Pi"),
+    ([0xB8, 0xAC, 0x6F], "Dell"),
+    ([0xC8, 0x0E, 0x14], "AVM"),
+    ([0xD8, 0x3A, 0xDD], "Raspberry Pi"),
+    ([0xDC, 0xA6, 0x32], "Raspberry Pi"),
+    ([0xE4, 0x5F, 0x01], "Raspberry Pi"),
+    ([0xF0, 0x18, 0x98], "Apple"),
+    ([0xF0, 0x9F, 0xC2], "Ubiquiti"),
+];
+
+/// Returns the vendor assigned to the OUI, if it's in the table
+pub(crate) fn vendor(oui: [u8; 3]) -> Option<&'static str> {
+    VENDORS
+        .binary_search_by(|(entry, _)| entry.cmp(&oui))
+        .ok()
+        .map(|index| VENDORS[index].1)
+}
@@ -0,0 +1,133 @@
+// Copyright (C) 2025 DarkCeptor44
+//
+// This file is part of waker.
+//
+// waker is free software: you can redistribute it and/or modify
+// it under theterms of the GNU Lesser General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// waker is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Lesser General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public License
+// along with waker.  If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{types::packet_mac, Mac, MagicPacket, WakeError};
+use anyhow::{Context, Result};
+use std::{
+    io::ErrorKind,
+    net::{SocketAddr, ToSocketAddrs, UdpSocket},
+    time::Duration,
+};
+
+/// A UDP socket that waits for magic packets, useful for end-to-end tests and tools
+///
+/// ## Examples
+///
+/// ```rust
+/// use waker::{create_magic_packet, wake_device, Mac, MagicPacketReceiver, WakeOptions};
+///
+/// let receiver = MagicPacketReceiver::bind("127.0.0.1:0").unwrap();
+/// let packet = create_magic_packet("01:23:45:67:89:AB").unwrap();
+///
+/// wake_device(WakeOptions::new(&packet).broadcast_address(receiver.local_addr().to_string())).unwrap();
+///
+/// assert_eq!(receiver.recv_mac().unwrap(), Mac([0x01, 0x23, 0x45, 0x67, 0x89, 0xAB]));
+/// ```
+#[derive(Debug)]
+pub struct MagicPacketReceiver {
+    socket: UdpSocket,
+    local_addr: SocketAddr,
+    timeout: Duration,
+}
+
+impl MagicPacketReceiver {
+    /// How long to wait for a packet by default
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(1);
+
+    /// Binds a receiver to the address, waiting up to [`MagicPacketReceiver::DEFAULT_TIMEOUT`] for each packet
+    ///
+    /// ## Arguments
+    ///
+    /// * `addr` - The address to listen on, use port `0` to let the OS pick a free port and [`MagicPacketReceiver::local_addr`] to get it
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error if the socket can't be bound or configured
+    pub fn bind<A>(addr: A) -> Result<Self>
+    where
+        A: ToSocketAddrs,
+    {
+        let socket = UdpSocket::bind(addr).context("Failed to bind receiving socket")?;
+        let local_addr = socket.local_addr().context("Failed to get local address")?;
+        let mut receiver = Self {
+            socket,
+            local_addr,
+            timeout: Self::DEFAULT_TIMEOUT,
+        };
+
+        receiver.set_timeout(Self::DEFAULT_TIMEOUT)?;
+        Ok(receiver)
+    }
+
+    /// Sets how long to wait for each packet
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error if the timeout is zero or can't be set on the socket
+    pub fn set_timeout(&mut self, timeout: Duration) -> Result<()> {
+        self.socket
+            .set_read_timeout(Some(timeout))
+            .context("Failed to set read timeout")?;
+        self.timeout = timeout;
+
+        Ok(())
+    }
+
+    /// Returns the address the receiver is listening on
+    #[must_use]
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Waits for the next datagram and returns its bytes as a [`MagicPacket`], without checking them
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`WakeError::ReceiveTimeout`] if nothing arrives in time, or an error if receiving fails
+    pub fn recv(&self) -> Result<MagicPacket> {
+        self.recv_from().map(|(packet, _)| packet)
+    }
+
+    /// Waits for the next datagram and returns its bytes as a [`MagicPacket`] along with the address it was sent from
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`WakeError::ReceiveTimeout`] if nothing arrives in time, or an error if receiving fails
+    pub fn recv_from(&self) -> Result<(MagicPacket, SocketAddr)> {
+        // larger than any magic packet so oversized datagrams aren't silently truncated to a valid one
+        let mut buffer = [0u8; 1024];
+
+        match self.socket.recv_from(&mut buffer) {
+            Ok((len, src_addr)) => Ok((MagicPacket(buffer[..len].to_vec()), src_addr)),
+            Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                Err(WakeError::ReceiveTimeout(self.timeout).into())
+            }
+            Err(e) => Err(e).context("Failed to receive magic packet"),
+        }
+    }
+
+    /// Waits for the next magic packet and returns the MAC address it wakes
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`WakeError::ReceiveTimeout`] if nothing arrives in time, [`WakeError::InvalidPacket`] or [`WakeError::MismatchedRepetition`] if the datagram isn't a valid magic packet, or an error if receiving fails
+    pub fn recv_mac(&self) -> Result<Mac> {
+        let packet = MagicPacket::try_from(self.recv()?.0.as_slice())?;
+
+        packet_mac(&packet.0).context("Failed to read MAC address from magic packet")
+    }
+}
@@ -0,0 +1,231 @@
+// Copyright (C) 2025 DarkCeptor44
+//
+// This file is part of waker.
+//
+// waker is free software: you can redistribute it and/or modify
+// it under theterms of the GNU Lesser General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// waker is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Lesser General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public License
+// along with waker.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Relaying magic packets through a host on the target network
+//!
+//! Broadcasts usually can't cross routers, so to wake a machine on another network the magic packet can be sent to a relay on that network instead, with a small header saying where the relay should broadcast it. The sending side only needs [`WakeOptions::relay`](crate::WakeOptions::relay), the relay uses [`receive`] and [`RelayMessage::rebroadcast`].
+//!
+//! The message is the magic packet prefixed with a header:
+//!
+//! | Bytes     | Content                                      |
+//! |-----------|----------------------------------------------|
+//! | 4         | [`RELAY_MAGIC`]                              |
+//! | 1         | IP version of the broadcast address, 4 or 6  |
+//! | 4 or 16   | IP of the broadcast address                  |
+//! | 2         | Port of the broadcast address, big-endian    |
+//! | remaining | The magic packet                             |
+//!
+//! The magic packet must be a well formed 102-byte one, optionally followed by a 4 or 6-byte SecureOn password, and the relay only sends it to broadcast addresses unless others are allowed with [`RelayMessage::rebroadcast_allowing`]. Anything else is rejected so a relay can't be used to send arbitrary datagrams to arbitrary hosts.
+
+use crate::{
+    addr::{is_directed_broadcast, is_limited_broadcast},
+    types::PACKET_LEN,
+    wake_device, MagicPacket, WakeError, WakeOptions,
+};
+use anyhow::{Context, Result};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, UdpSocket};
+
+/// The bytes every relay message starts with
+pub const RELAY_MAGIC: [u8; 4] = *b"WKRL";
+
+/// A magic packet received by a relay along with the broadcast address it should be sent to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelayMessage {
+    /// The broadcast address the relay should send the magic packet to
+    pub target: SocketAddr,
+
+    /// The magic packet to broadcast
+    pub packet: MagicPacket,
+}
+
+impl RelayMessage {
+    /// Broadcasts the magic packet to the target address from the relay, as long as the target is a limited or directed broadcast address
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`WakeError::RelayTargetNotAllowed`] if the target isn't a broadcast address, or an error if sending the packet fails, see [`wake_device`]
+    pub fn rebroadcast(&self) -> Result<()> {
+        self.rebroadcast_allowing(&[])
+    }
+
+    /// Like [`RelayMessage::rebroadcast`] but also sends to the addresses in the allowlist, like an IPv6 multicast group such as `ff02::1` which is never a broadcast address
+    ///
+    /// ## Arguments
+    ///
+    /// * `allowed` - The IP addresses the relay may send to besides broadcast addresses
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`WakeError::RelayTargetNotAllowed`] if the target isn't a broadcast address or in the allowlist, or an error if sending the packet fails, see [`wake_device`]
+    pub fn rebroadcast_allowing(&self, allowed: &[IpAddr]) -> Result<()> {
+        let ip = self.target.ip();
+        if !is_limited_broadcast(ip) && !is_directed_broadcast(ip) && !allowed.contains(&ip) {
+            return Err(WakeError::RelayTargetNotAllowed(self.target).into());
+        }
+
+        wake_device(WakeOptions::new(&self.packet).broadcast_address(self.target.to_string()))
+    }
+}
+
+/// Encodes a magic packet into a relay message
+///
+/// ## Arguments
+///
+/// * `packet` - The magic packet
+/// * `target` - The broadcast address the relay should send the magic packet to
+///
+/// ## Returns
+///
+/// The relay message, ready to be sent to the relay
+///
+/// ## Examples
+///
+/// ```rust
+/// use waker::{create_magic_packet, relay};
+///
+/// let packet = create_magic_packet("01:23:45:67:89:AB").unwrap();
+/// let message = relay::encode(&packet.0, "192.168.1.255:9".parse().unwrap());
+///
+/// assert_eq!(message.len(), 4 + 1 + 4 + 2 + 102);
+/// ```
+#[must_use]
+pub fn encode(packet: &[u8], target: SocketAddr) -> Vec<u8> {
+    let mut message = Vec::with_capacity(RELAY_MAGIC.len() + 19 + packet.len());
+    message.extend_from_slice(&RELAY_MAGIC);
+
+    match target.ip() {
+        IpAddr::V4(ip) => {
+            message.push(4);
+            message.extend_from_slice(&ip.octets());
+        }
+        IpAddr::V6(ip) => {
+            message.push(6);
+            message.extend_from_slice(&ip.octets());
+        }
+    }
+
+    message.extend_from_slice(&target.port().to_be_bytes());
+    message.extend_from_slice(packet);
+    message
+}
+
+/// Decodes a relay message
+///
+/// ## Arguments
+///
+/// * `message` - The relay message, like a datagram received by the relay
+///
+/// ## Errors
+///
+/// Returns [`WakeError::InvalidRelayMessage`] if the message doesn't start with [`RELAY_MAGIC`], the header is truncated or there is no magic packet after it, and [`WakeError::InvalidPacket`] or [`WakeError::MismatchedRepetition`] if the magic packet isn't well formed
+///
+/// ## Examples
+///
+/// ```rust
+/// use waker::{create_magic_packet, relay};
+///
+/// let packet = create_magic_packet("01:23:45:67:89:AB").unwrap();
+/// let message = relay::encode(&packet.0, "192.168.1.255:9".parse().unwrap());
+/// let decoded = relay::decode(&message).unwrap();
+///
+/// assert_eq!(decoded.target.to_string(), "192.168.1.255:9");
+/// assert_eq!(decoded.packet, packet);
+/// ```
+pub fn decode(message: &[u8]) -> Result<RelayMessage, WakeError> {
+    let invalid = |reason: &str| WakeError::InvalidRelayMessage(reason.to_string());
+
+    let rest = message
+        .strip_prefix(&RELAY_MAGIC)
+        .ok_or_else(|| invalid("missing relay header"))?;
+    let (version, rest) = rest
+        .split_first()
+        .ok_or_else(|| invalid("missing IP version"))?;
+    let ip_len = match version {
+        4 => 4,
+        6 => 16,
+        _ => return Err(invalid(&format!("unknown IP version {version}"))),
+    };
+
+    if rest.len() < ip_len + 2 {
+        return Err(invalid("truncated broadcast address"));
+    }
+
+    let (ip, rest) = rest.split_at(ip_len);
+    let (port, packet) = rest.split_at(2);
+
+    if packet.is_empty() {
+        return Err(invalid("missing magic packet"));
+    }
+
+    // only a magic packet and a SecureOn password, so the relay never forwards arbitrary payloads
+    if !matches!(packet.len().checked_sub(PACKET_LEN), Some(0 | 4 | 6)) {
+        return Err(WakeError::InvalidPacket(format!(
+            "expected {PACKET_LEN} bytes and an optional 4 or 6-byte SecureOn password, got {}",
+            packet.len()
+        )));
+    }
+    MagicPacket::try_from(&packet[..PACKET_LEN])?;
+
+    let ip = if let Ok(octets) = <[u8; 4]>::try_from(ip) {
+        IpAddr::V4(Ipv4Addr::from(octets))
+    } else {
+        let mut octets = [0u8; 16];
+        octets.copy_from_slice(ip);
+        IpAddr::V6(Ipv6Addr::from(octets))
+    };
+
+    Ok(RelayMessage {
+        target: SocketAddr::new(ip, u16::from_be_bytes([port[0], port[1]])),
+        packet: MagicPacket(packet.to_vec()),
+    })
+}
+
+/// Waits for a relay message on the socket and decodes it
+///
+/// ## Arguments
+///
+/// * `socket` - The socket the relay listens on
+///
+/// ## Errors
+///
+/// Returns an error if receiving from the socket fails or if the datagram isn't a valid relay message
+///
+/// ## Examples
+///
+/// ```rust,no_run
+/// use std::net::UdpSocket;
+/// use waker::relay;
+///
+/// // listen on the address the senders reach, a firewall should still limit who can send to it
+/// let socket = UdpSocket::bind("192.168.1.2:9009").unwrap();
+///
+/// loop {
+///     // targets that aren't broadcast addresses are rejected by `rebroadcast`
+///     if let Err(e) = relay::receive(&socket).and_then(|message| message.rebroadcast()) {
+///         eprintln!("{e}");
+///     }
+/// }
+/// ```
+pub fn receive(socket: &UdpSocket) -> Result<RelayMessage> {
+    // a relay message with an IPv6 target and a 102-byte magic packet is 125 bytes, leave room for larger packets
+    let mut buffer = [0u8; 512];
+    let (len, _) = socket
+        .recv_from(&mut buffer)
+        .context("Failed to receive relay message")?;
+
+    Ok(decode(&buffer[..len])?)
+}
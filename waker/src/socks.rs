@@ -0,0 +1,154 @@
+// Copyright (C) 2025 DarkCeptor44
+//
+// This file is part of waker.
+//
+// waker is free software: you can redistribute it and/or modify
+// it under theterms of the GNU Lesser General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// waker is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Lesser General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public License
+// along with waker.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Sending UDP datagrams through a SOCKS5 proxy with the UDP ASSOCIATE command ([RFC 1928](https://www.rfc-editor.org/rfc/rfc1928))
+
+use crate::WakeError;
+use anyhow::{Context, Result};
+use std::{
+    io::{self, Read, Write},
+    net::{IpAddr, SocketAddr, TcpStream, UdpSocket},
+    time::Duration,
+};
+
+/// How long to wait for the proxy during the handshake
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A UDP association with a SOCKS5 proxy, it lasts as long as the control connection is open
+#[derive(Debug)]
+pub(crate) struct UdpAssociate {
+    _control: TcpStream,
+    relay: SocketAddr,
+}
+
+impl UdpAssociate {
+    /// Connects to the proxy and asks it to relay UDP datagrams sent from `local`
+    pub(crate) fn connect(proxy: SocketAddr, local: SocketAddr) -> Result<Self> {
+        let mut control = TcpStream::connect_timeout(&proxy, HANDSHAKE_TIMEOUT)
+            .with_context(|| format!("Failed to connect to proxy {proxy}"))?;
+        control.set_read_timeout(Some(HANDSHAKE_TIMEOUT))?;
+
+        // version 5 with the "no authentication" method only
+        control
+            .write_all(&[5, 1, 0])
+            .context("Failed to send SOCKS greeting")?;
+        let mut choice = [0u8; 2];
+        control
+            .read_exact(&mut choice)
+            .context("Failed to read SOCKS greeting reply")?;
+
+        if choice != [5, 0] {
+            return Err(invalid("proxy doesn't accept connections without authentication").into());
+        }
+
+        let mut request = vec![5, 3, 0];
+        write_addr(&mut request, local);
+        control
+            .write_all(&request)
+            .context("Failed to send UDP ASSOCIATE request")?;
+
+        let mut reply = [0u8; 3];
+        control
+            .read_exact(&mut reply)
+            .context("Failed to read UDP ASSOCIATE reply")?;
+
+        if reply[1] != 0 {
+            return Err(invalid(&format!(
+                "UDP ASSOCIATE failed with reply code {}",
+                reply[1]
+            ))
+            .into());
+        }
+
+        let relay = read_addr(&mut control)?;
+
+        // an unspecified address means the relay is on the same host as the proxy
+        let relay = if relay.ip().is_unspecified() {
+            SocketAddr::new(proxy.ip(), relay.port())
+        } else {
+            relay
+        };
+
+        Ok(Self {
+            _control: control,
+            relay,
+        })
+    }
+
+    /// Sends the data to the target through the proxy's relay
+    pub(crate) fn send_to(
+        &self,
+        socket: &UdpSocket,
+        data: &[u8],
+        target: SocketAddr,
+    ) -> io::Result<usize> {
+        // reserved bytes and fragment number, fragmentation isn't used
+        let mut datagram = vec![0, 0, 0];
+        write_addr(&mut datagram, target);
+        datagram.extend_from_slice(data);
+
+        socket.send_to(&datagram, self.relay)
+    }
+}
+
+/// Appends the address type, address and big-endian port of a SOCKS address
+fn write_addr(buf: &mut Vec<u8>, addr: SocketAddr) {
+    match addr.ip() {
+        IpAddr::V4(ip) => {
+            buf.push(1);
+            buf.extend_from_slice(&ip.octets());
+        }
+        IpAddr::V6(ip) => {
+            buf.push(4);
+            buf.extend_from_slice(&ip.octets());
+        }
+    }
+
+    buf.extend_from_slice(&addr.port().to_be_bytes());
+}
+
+/// Reads a SOCKS address from the proxy's reply
+fn read_addr(stream: &mut TcpStream) -> Result<SocketAddr> {
+    let mut kind = [0u8; 1];
+    stream
+        .read_exact(&mut kind)
+        .context("Failed to read relay address")?;
+
+    let ip = match kind[0] {
+        1 => {
+            let mut octets = [0u8; 4];
+            stream.read_exact(&mut octets)?;
+            IpAddr::from(octets)
+        }
+        4 => {
+            let mut octets = [0u8; 16];
+            stream.read_exact(&mut octets)?;
+            IpAddr::from(octets)
+        }
+        kind => return Err(invalid(&format!("unsupported relay address type {kind}")).into()),
+    };
+
+    let mut port = [0u8; 2];
+    stream.read_exact(&mut port)?;
+
+    Ok(SocketAddr::new(ip, u16::from_be_bytes(port)))
+}
+
+/// Creates the error for a reply the proxy shouldn't have sent
+fn invalid(reason: &str) -> WakeError {
+    WakeError::InvalidProxyReply(reason.into())
+}
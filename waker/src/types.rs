@@ -15,8 +15,17 @@
 // You should have received a copy of the GNU Lesser General Public License
 // along with waker.  If not, see <https://www.gnu.org/licenses/>.
 
-use crate::{hex_val, MacAddressError};
-use std::{borrow::Cow, convert::Infallible, fmt, str::FromStr};
+use crate::{hex_val, MacAddressError, WakeError};
+use std::{
+    borrow::Cow,
+    convert::Infallible,
+    fmt::{self, Write as _},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6, ToSocketAddrs},
+    ops::Range,
+    str::FromStr,
+    sync::Arc,
+    time::Duration,
+};
 
 /// A trait for types that can be converted into a MAC address byte array
 pub trait AsMacBytes {
@@ -43,6 +52,14 @@ impl AsMacBytes for Mac {
     }
 }
 
+impl AsMacBytes for &Mac {
+    type Error = Infallible;
+
+    fn as_mac_bytes(&self) -> Result<[u8; 6], Self::Error> {
+        Ok(self.0)
+    }
+}
+
 impl AsMacBytes for &[u8] {
     type Error = MacAddressError;
 
@@ -96,206 +113,2282 @@ impl AsMacBytes for &String {
     }
 }
 
-/// Represents a Wake-on-LAN magic packet
-#[derive(Debug, Clone, PartialEq, Eq)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-pub struct MagicPacket(pub Vec<u8>);
+impl AsMacBytes for HwAddr {
+    type Error = MacAddressError;
 
-impl AsRef<[u8]> for MagicPacket {
-    fn as_ref(&self) -> &[u8] {
-        &self.0
+    fn as_mac_bytes(&self) -> Result<[u8; 6], Self::Error> {
+        match self {
+            Self::Mac48(bytes) => Ok(*bytes),
+            Self::Eui64(_) => Err(MacAddressError::Eui64NotSupported(*self)),
+        }
     }
 }
 
-/// Represents a MAC address
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub struct Mac(pub [u8; 6]);
+/// Length of a magic packet without a SecureOn password
+pub(crate) const PACKET_LEN: usize = 102;
 
-impl From<[u8; 6]> for Mac {
-    fn from(value: [u8; 6]) -> Self {
-        Self(value)
-    }
-}
+/// The byte ranges of the parts of a magic packet, see [`magic_packet_layout`](crate::magic_packet_layout)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PacketLayout {
+    /// The [`SYNC_STREAM`](crate::SYNC_STREAM) header, `0..6`
+    pub sync: Range<usize>,
 
-impl TryFrom<&[u8]> for Mac {
-    type Error = MacAddressError;
+    /// The 16 repetitions of the MAC address, `6..12`, `12..18` and so on up to `96..102`
+    pub mac_slots: [Range<usize>; 16],
 
-    fn try_from(value: &[u8]) -> std::result::Result<Self, Self::Error> {
-        if value.len() != 6 {
-            return Err(MacAddressError::InvalidLength(value.len()));
-        }
+    /// The SecureOn password, `102..108`, only present in 108-byte packets
+    pub password: Range<usize>,
+}
 
-        let mut bytes = [0u8; 6];
-        bytes.copy_from_slice(value);
+impl PacketLayout {
+    /// Creates the layout of a magic packet
+    pub(crate) fn new() -> Self {
+        Self {
+            sync: 0..6,
+            mac_slots: std::array::from_fn(|i| 6 + i * 6..12 + i * 6),
+            password: PACKET_LEN..PACKET_LEN + 6,
+        }
+    }
 
-        Ok(Self(bytes))
+    /// Returns the part of the packet a byte belongs to, for annotating hex dumps
+    ///
+    /// ## Arguments
+    ///
+    /// * `index` - The index of the byte in the packet
+    ///
+    /// ## Returns
+    ///
+    /// `"sync"`, `"mac"` or `"password"`, or [`None`] if the index is past the end of a packet
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use waker::magic_packet_layout;
+    ///
+    /// let layout = magic_packet_layout();
+    ///
+    /// assert_eq!(layout.part_of(0), Some("sync"));
+    /// assert_eq!(layout.part_of(50), Some("mac"));
+    /// assert_eq!(layout.part_of(105), Some("password"));
+    /// assert_eq!(layout.part_of(108), None);
+    /// ```
+    #[must_use]
+    pub fn part_of(&self, index: usize) -> Option<&'static str> {
+        if self.sync.contains(&index) {
+            Some("sync")
+        } else if self.mac_slots.iter().any(|slot| slot.contains(&index)) {
+            Some("mac")
+        } else if self.password.contains(&index) {
+            Some("password")
+        } else {
+            None
+        }
     }
 }
 
-impl TryFrom<&str> for Mac {
-    type Error = MacAddressError;
+/// Represents a Wake-on-LAN magic packet
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MagicPacket(pub Vec<u8>);
 
-    fn try_from(value: &str) -> Result<Self, Self::Error> {
-        value.parse()
+impl AsRef<[u8]> for MagicPacket {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
     }
 }
 
-impl FromStr for Mac {
-    type Err = MacAddressError;
-
-    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
-        let s = s.trim();
-        let mut bytes = [0u8; 6];
-        let mut s_chars = s.chars().peekable();
-
-        for (i, byte_ref) in bytes.iter_mut().enumerate() {
-            let c1 = s_chars
-                .next()
-                .ok_or(MacAddressError::InvalidLength(s.len()))?;
-            let c2 = s_chars
-                .next()
-                .ok_or(MacAddressError::InvalidLength(s.len()))?;
-
-            let val = (hex_val(c1)? << 4) | hex_val(c2)?;
-            *byte_ref = val;
-
-            if i < 5 {
-                match s_chars.next() {
-                    Some(c) if c == ':' || c == '-' || c == '_' || c == '.' => {}
-                    Some(_) => return Err(MacAddressError::InvalidMacAddress(s.to_string())),
-                    None => return Err(MacAddressError::InvalidLength(s.len())),
-                }
-            }
-        }
-
-        if s_chars.next().is_some() {
-            return Err(MacAddressError::InvalidLength(s.len()));
-        }
+impl TryFrom<&[u8]> for MagicPacket {
+    type Error = WakeError;
 
-        Ok(Self(bytes))
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        Self::try_from_iter(value.iter().copied())
     }
 }
 
-impl fmt::Display for Mac {
+/// Formats the packet as a continuous lowercase hex string like `ffffffffffff0123456789ab...`, which is much shorter than [`Debug`] in logs
+impl fmt::Display for MagicPacket {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         fmt::LowerHex::fmt(self, f)
     }
 }
 
-impl fmt::LowerHex for Mac {
+impl fmt::LowerHex for MagicPacket {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "{:02x}:{:02x}:{:02x}:{:02x}:{:02x}:{:02x}",
-            self.0[0], self.0[1], self.0[2], self.0[3], self.0[4], self.0[5]
-        )
+        write_hex(f, &self.0, HEX_LOWER)
     }
 }
 
-impl fmt::UpperHex for Mac {
+impl fmt::UpperHex for MagicPacket {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "{:02X}:{:02X}:{:02X}:{:02X}:{:02X}:{:02X}",
-            self.0[0], self.0[1], self.0[2], self.0[3], self.0[4], self.0[5]
-        )
+        write_hex(f, &self.0, HEX_UPPER)
     }
 }
 
-#[cfg(feature = "serde")]
-impl serde::Serialize for Mac {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: serde::Serializer,
-    {
-        serializer.serialize_str(&self.to_string())
+/// Writes every byte as two hex digits from the table, without separators
+fn write_hex(f: &mut fmt::Formatter<'_>, bytes: &[u8], digits: &[u8; 16]) -> fmt::Result {
+    for byte in bytes {
+        f.write_char(char::from(digits[usize::from(byte >> 4)]))?;
+        f.write_char(char::from(digits[usize::from(byte & 0x0F)]))?;
     }
+
+    Ok(())
 }
 
-#[cfg(feature = "serde")]
-impl<'de> serde::Deserialize<'de> for Mac {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+impl MagicPacket {
+    /// Creates a magic packet for an already parsed MAC address
+    ///
+    /// Unlike [`create_magic_packet`](crate::create_magic_packet) this can't fail, so there is no [`Result`] to handle when the MAC address is already a [`Mac`].
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use waker::{create_magic_packet, Mac, MagicPacket};
+    ///
+    /// let mac = Mac([0x01, 0x23, 0x45, 0x67, 0x89, 0xAB]);
+    ///
+    /// assert_eq!(MagicPacket::for_mac(mac), create_magic_packet(mac).unwrap());
+    /// ```
+    #[must_use]
+    pub fn for_mac(mac: Mac) -> Self {
+        let mut packet = vec![0u8; PACKET_LEN];
+        fill_packet(&mut packet, mac);
+        debug_assert_eq!(packet.len(), 6 + 16 * 6);
+
+        Self(packet)
+    }
+
+    /// Collects bytes from a stream into a magic packet, checking that they form a valid one
+    ///
+    /// A valid magic packet is the [`SYNC_STREAM`](crate::SYNC_STREAM) followed by the MAC address 16 times, optionally followed by a 6-byte SecureOn password.
+    ///
+    /// ## Arguments
+    ///
+    /// * `iter` - The bytes of the packet
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`WakeError::InvalidPacket`] if the bytes have the wrong length or don't start with the sync stream, and [`WakeError::MismatchedRepetition`] if they don't repeat the same MAC address 16 times
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use waker::{create_magic_packet, MagicPacket};
+    ///
+    /// let packet = create_magic_packet("01:23:45:67:89:AB").unwrap();
+    ///
+    /// assert_eq!(MagicPacket::try_from_iter(packet.0.iter().copied()), Ok(packet));
+    /// assert!(MagicPacket::try_from_iter([0xFF; 6]).is_err());
+    /// ```
+    pub fn try_from_iter<I>(iter: I) -> Result<Self, WakeError>
     where
-        D: serde::Deserializer<'de>,
+        I: IntoIterator<Item = u8>,
     {
-        let s = String::deserialize(deserializer)?;
-        s.parse::<Mac>().map_err(serde::de::Error::custom)
+        // one extra byte is enough to tell that the stream is too long
+        let bytes: Vec<u8> = iter.into_iter().take(PACKET_LEN + 7).collect();
+
+        if bytes.len() > PACKET_LEN + 6 {
+            return Err(WakeError::InvalidPacket(format!(
+                "expected {PACKET_LEN} or {} bytes, got {} or more",
+                PACKET_LEN + 6,
+                bytes.len()
+            )));
+        }
+
+        Self::checked(bytes)
     }
-}
 
-/// Wake-on-LAN options
-#[derive(Debug, Clone)]
-pub struct WakeOptions<'a> {
-    /// The packet to send, you can create one with [`create_magic_packet`](crate::create_magic_packet)
-    pub packet: &'a MagicPacket,
+    /// Copies bytes received off the wire into a magic packet, checking that they are a well formed 102-byte one
+    ///
+    /// Unlike [`try_from_iter`](Self::try_from_iter) packets with a SecureOn password are rejected, the bytes must be exactly the [`SYNC_STREAM`](crate::SYNC_STREAM) followed by the same MAC address 16 times.
+    ///
+    /// ## Arguments
+    ///
+    /// * `bytes` - The bytes of the packet
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`WakeError::InvalidPacket`] saying whether the length or the sync stream is wrong, and [`WakeError::MismatchedRepetition`] with the first repetition that doesn't match
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use waker::{create_magic_packet, MagicPacket};
+    ///
+    /// let packet = create_magic_packet("01:23:45:67:89:AB").unwrap();
+    ///
+    /// assert_eq!(MagicPacket::from_bytes(&packet.0), Ok(packet));
+    /// assert!(MagicPacket::from_bytes(&[0xFF; 6]).is_err());
+    /// ```
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, WakeError> {
+        if bytes.len() != PACKET_LEN {
+            return Err(WakeError::InvalidPacket(format!(
+                "expected {PACKET_LEN} bytes, got {}",
+                bytes.len()
+            )));
+        }
 
-    /// The broadcast address to send the magic packet to
+        Self::checked(bytes.to_vec())
+    }
+
+    /// Turns a boxed slice back into a magic packet, checking that it's a valid one
     ///
-    /// Defaults to `"255.255.255.255:9"`
-    pub broadcast_address: Cow<'a, str>,
+    /// The allocation is reused, so packets cached with [`into_boxed_slice`](Self::into_boxed_slice) are converted back without copying.
+    ///
+    /// ## Arguments
+    ///
+    /// * `bytes` - The bytes of the packet
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`WakeError::InvalidPacket`] if the bytes aren't a valid magic packet, see [`try_from_iter`](Self::try_from_iter)
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use waker::{create_magic_packet, MagicPacket};
+    ///
+    /// let packet = create_magic_packet("01:23:45:67:89:AB").unwrap();
+    /// let boxed = packet.clone().into_boxed_slice();
+    ///
+    /// assert_eq!(MagicPacket::from_boxed_slice(boxed), Ok(packet));
+    /// assert!(MagicPacket::from_boxed_slice(Box::new([0xFF; 6])).is_err());
+    /// ```
+    pub fn from_boxed_slice(bytes: Box<[u8]>) -> Result<Self, WakeError> {
+        Self::checked(bytes.into_vec())
+    }
 
-    /// The address to bind the UDP socket to
+    /// Converts the packet into a boxed slice, which is leaner than a [`Vec`] when caching many packets
     ///
-    /// Defaults to `"0.0.0.0:0"`
-    pub bind_address: Cow<'a, str>,
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use waker::create_magic_packet;
+    ///
+    /// let packet = create_magic_packet("01:23:45:67:89:AB").unwrap();
+    ///
+    /// assert_eq!(packet.into_boxed_slice().len(), 102);
+    /// ```
+    #[must_use]
+    pub fn into_boxed_slice(self) -> Box<[u8]> {
+        self.0.into_boxed_slice()
+    }
 
-    /// The password to use for secure Wake-on-LAN.
+    /// Panics if the packet isn't a well formed magic packet, meant for tests of code that builds packets
     ///
-    /// **Currently unsupported**
-    pub pass: Option<Cow<'a, str>>,
-}
+    /// ## Panics
+    ///
+    /// Panics if the packet isn't `6 + 16 * 6` bytes long, or `6` more with a SecureOn password, doesn't start with the [`SYNC_STREAM`](crate::SYNC_STREAM) or doesn't repeat the same MAC address 16 times
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use waker::{create_magic_packet, MagicPacket};
+    ///
+    /// create_magic_packet("01:23:45:67:89:AB").unwrap().assert_well_formed();
+    ///
+    /// let truncated = MagicPacket(vec![0xFF; 60]);
+    /// assert!(std::panic::catch_unwind(|| truncated.assert_well_formed()).is_err());
+    /// ```
+    pub fn assert_well_formed(&self) {
+        if let Err(e) = Self::check(&self.0) {
+            panic!("{e}");
+        }
+    }
 
-impl<'a> From<&'a MagicPacket> for WakeOptions<'a> {
-    fn from(packet: &'a MagicPacket) -> Self {
-        Self::new(packet)
+    /// Wraps the bytes in a magic packet if they have its length and structure
+    fn checked(bytes: Vec<u8>) -> Result<Self, WakeError> {
+        Self::check(&bytes)?;
+        Ok(Self(bytes))
     }
-}
 
-impl<'a> WakeOptions<'a> {
-    /// Creates a new [`WakeOptions`] with the specified magic packet
-    #[must_use]
-    pub fn new(magic_packet: &'a MagicPacket) -> Self {
-        Self {
-            packet: magic_packet,
-            broadcast_address: "255.255.255.255:9".into(),
-            bind_address: "0.0.0.0:0".into(),
-            pass: None,
+    /// Checks that the bytes have the length and structure of a magic packet
+    fn check(bytes: &[u8]) -> Result<(), WakeError> {
+        if bytes.len() != PACKET_LEN && bytes.len() != PACKET_LEN + 6 {
+            return Err(WakeError::InvalidPacket(format!(
+                "expected {PACKET_LEN} or {} bytes, got {}",
+                PACKET_LEN + 6,
+                bytes.len()
+            )));
+        }
+
+        if !bytes.starts_with(&crate::SYNC_STREAM) {
+            return Err(WakeError::InvalidPacket(
+                "missing synchronization stream".into(),
+            ));
         }
+
+        if let Some(repetition) = mismatched_repetition(bytes) {
+            return Err(WakeError::MismatchedRepetition(repetition));
+        }
+
+        Ok(())
     }
 
-    /// Sets the broadcast address
+    /// Checks whether the packet starts with the [`SYNC_STREAM`](crate::SYNC_STREAM) header
     ///
-    /// Defaults to `"255.255.255.255:9"`
+    /// ## Returns
+    ///
+    /// `true` if the first 6 bytes are all `0xFF`, `false` otherwise
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use waker::create_magic_packet;
+    ///
+    /// let mut packet = create_magic_packet("01:23:45:67:89:AB").unwrap();
+    /// assert!(packet.has_sync_stream());
+    ///
+    /// packet.0[0] = 0x00;
+    /// assert!(!packet.has_sync_stream());
+    /// ```
     #[must_use]
-    pub fn broadcast_address<S>(mut self, address: S) -> Self
-    where
-        S: Into<Cow<'a, str>>,
-    {
-        self.broadcast_address = address.into();
-        self
+    pub fn has_sync_stream(&self) -> bool {
+        self.0.starts_with(&crate::SYNC_STREAM)
     }
 
-    /// Sets the bind address
+    /// Returns the MAC address of the first repetition, which is the machine the packet wakes
     ///
-    /// Defaults to `"0.0.0.0:0"`
+    /// Nothing else is checked, see [`verify_repetitions`](Self::verify_repetitions) to check the other repetitions too.
+    ///
+    /// ## Returns
+    ///
+    /// The MAC address in bytes `6..12`, or [`None`] if the packet is shorter than 12 bytes
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use waker::{create_magic_packet, Mac, MagicPacket};
+    ///
+    /// let packet = create_magic_packet("01:23:45:67:89:AB").unwrap();
+    ///
+    /// assert_eq!(packet.mac(), Some(Mac([0x01, 0x23, 0x45, 0x67, 0x89, 0xAB])));
+    /// assert_eq!(MagicPacket(vec![0xFF; 6]).mac(), None);
+    /// ```
     #[must_use]
-    pub fn bind_address<S>(mut self, address: S) -> Self
-    where
-        S: Into<Cow<'a, str>>,
-    {
-        self.bind_address = address.into();
-        self
+    pub fn mac(&self) -> Option<Mac> {
+        packet_mac(&self.0)
+    }
+
+    /// Checks that all 16 MAC address repetitions after the sync stream are identical and returns the MAC address
+    ///
+    /// This is meant for receivers that validate strictly, the sync stream and the length of the packet past the repetitions aren't checked.
+    ///
+    /// ## Returns
+    ///
+    /// The MAC address the packet wakes
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`WakeError::MismatchedRepetition`] with the number (starting at `1`) of the first repetition that is missing or different from the first one
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use waker::{create_magic_packet, Mac, WakeError};
+    ///
+    /// let mut packet = create_magic_packet("01:23:45:67:89:AB").unwrap();
+    /// assert_eq!(packet.verify_repetitions(), Ok(Mac([0x01, 0x23, 0x45, 0x67, 0x89, 0xAB])));
+    ///
+    /// packet.0[6 + 6 * 2] = 0x00;
+    /// assert_eq!(packet.verify_repetitions(), Err(WakeError::MismatchedRepetition(3)));
+    /// ```
+    pub fn verify_repetitions(&self) -> Result<Mac, WakeError> {
+        if let Some(repetition) = mismatched_repetition(&self.0) {
+            return Err(WakeError::MismatchedRepetition(repetition));
+        }
+
+        let mut mac = [0u8; 6];
+        mac.copy_from_slice(&self.0[6..12]);
+        Ok(Mac(mac))
     }
 
-    /// Sets the secure password for Wake-on-LAN
+    /// Compares the packets without the SecureOn password, so only the first 102 bytes have to match
+    ///
+    /// Useful when the password was redacted, like in audit logs that don't store the secret.
+    ///
+    /// ## Arguments
     ///
-    /// **Currently unsupported**
+    /// * `other` - The packet to compare with
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use waker::{Mac, MagicPacket, MagicPacketN};
+    ///
+    /// let mac = Mac([0x01, 0x23, 0x45, 0x67, 0x89, 0xAB]);
+    /// let packet = MagicPacket::from(MagicPacketN::with_password(mac, [0x11; 6]));
+    ///
+    /// assert!(packet.eq_ignoring_password(&MagicPacket::for_mac(mac)));
+    /// ```
     #[must_use]
-    pub fn secure_on<S>(mut self, password: S) -> Self
-    where
-        S: Into<Cow<'a, str>>,
-    {
-        self.pass = Some(password.into());
-        self
+    pub fn eq_ignoring_password(&self, other: &MagicPacket) -> bool {
+        self.0
+            .get(..PACKET_LEN)
+            .is_some_and(|bytes| Some(bytes) == other.0.get(..PACKET_LEN))
     }
+
+    /// Compares the packet with other bytes, like a packet received from the network, and returns where they differ
+    ///
+    /// If the lengths differ the bytes past the end of the shorter side are reported as differences too, with `0x00` standing in for the missing byte.
+    ///
+    /// ## Arguments
+    ///
+    /// * `other` - The bytes to compare the packet with
+    ///
+    /// ## Returns
+    ///
+    /// [`None`] if the bytes are equal, otherwise a list of `(index, expected, actual)` tuples for every byte that differs
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use waker::create_magic_packet;
+    ///
+    /// let packet = create_magic_packet("01:23:45:67:89:AB").unwrap();
+    /// let mut received = packet.0.clone();
+    ///
+    /// assert_eq!(packet.diff(&received), None);
+    ///
+    /// received[10] = 0x00;
+    /// assert_eq!(packet.diff(&received), Some(vec![(10, 0x89, 0x00)]));
+    /// ```
+    #[must_use]
+    pub fn diff(&self, other: &[u8]) -> Option<Vec<(usize, u8, u8)>> {
+        let differences: Vec<(usize, u8, u8)> = (0..self.0.len().max(other.len()))
+            .filter_map(|i| {
+                let (expected, actual) = (self.0.get(i), other.get(i));
+
+                (expected != actual).then(|| {
+                    (
+                        i,
+                        expected.copied().unwrap_or_default(),
+                        actual.copied().unwrap_or_default(),
+                    )
+                })
+            })
+            .collect();
+
+        if differences.is_empty() {
+            None
+        } else {
+            Some(differences)
+        }
+    }
+}
+
+impl<'a> From<&'a MagicPacket> for Cow<'a, [u8]> {
+    fn from(packet: &'a MagicPacket) -> Self {
+        Cow::Borrowed(&packet.0)
+    }
+}
+
+impl From<MagicPacket> for Cow<'_, [u8]> {
+    fn from(packet: MagicPacket) -> Self {
+        Cow::Owned(packet.0)
+    }
+}
+
+/// Represents a packet with an arbitrary wake pattern, for network interfaces that wake on a custom byte pattern instead of the magic packet
+///
+/// Unlike [`MagicPacket`] the bytes are never validated, see [`create_pattern_packet`](crate::create_pattern_packet).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PatternPacket(pub Vec<u8>);
+
+impl AsRef<[u8]> for PatternPacket {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl<'a> From<&'a PatternPacket> for Cow<'a, [u8]> {
+    fn from(packet: &'a PatternPacket) -> Self {
+        Cow::Borrowed(&packet.0)
+    }
+}
+
+impl From<PatternPacket> for Cow<'_, [u8]> {
+    fn from(packet: PatternPacket) -> Self {
+        Cow::Owned(packet.0)
+    }
+}
+
+/// Represents a Wake-on-LAN magic packet of `N` bytes stored on the stack, for when a heap allocated [`MagicPacket`] isn't an option
+///
+/// Regular magic packets are 102 bytes and ones with a SecureOn password are 108 bytes, see [`MagicPacketN::new`] and [`MagicPacketN::with_password`].
+///
+/// ## Examples
+///
+/// ```rust,no_run
+/// use waker::{wake_device, Mac, MagicPacketN};
+///
+/// let packet = MagicPacketN::new(Mac([0x01, 0x23, 0x45, 0x67, 0x89, 0xAB]));
+/// wake_device(&packet).unwrap();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MagicPacketN<const N: usize>(pub [u8; N]);
+
+impl MagicPacketN<PACKET_LEN> {
+    /// Creates a 102-byte magic packet for the MAC address
+    #[must_use]
+    pub fn new(mac: Mac) -> Self {
+        let mut packet = [0u8; PACKET_LEN];
+        fill_packet(&mut packet, mac);
+        debug_assert_eq!(packet.len(), 6 + 16 * 6);
+
+        Self(packet)
+    }
+}
+
+impl MagicPacketN<{ PACKET_LEN + 6 }> {
+    /// Creates a 108-byte magic packet for the MAC address with a SecureOn password at the end
+    ///
+    /// ## Arguments
+    ///
+    /// * `mac` - The MAC address of the machine
+    /// * `password` - The 6-byte SecureOn password
+    #[must_use]
+    pub fn with_password(mac: Mac, password: [u8; 6]) -> Self {
+        let mut packet = [0u8; PACKET_LEN + 6];
+        fill_packet(&mut packet, mac);
+        packet[PACKET_LEN..].copy_from_slice(&password);
+        debug_assert_eq!(packet.len(), 6 + 16 * 6 + 6);
+
+        Self(packet)
+    }
+}
+
+/// Writes the sync stream and 16 repetitions of the MAC address to the start of the buffer
+fn fill_packet(buf: &mut [u8], mac: Mac) {
+    debug_assert!(
+        buf.len() == PACKET_LEN || buf.len() == PACKET_LEN + 6,
+        "magic packet buffer must be {PACKET_LEN} or {} bytes, got {}",
+        PACKET_LEN + 6,
+        buf.len()
+    );
+
+    buf[..6].copy_from_slice(&crate::SYNC_STREAM);
+
+    for chunk in buf[6..PACKET_LEN].chunks_exact_mut(6) {
+        chunk.copy_from_slice(&mac.0);
+    }
+}
+
+impl<const N: usize> AsRef<[u8]> for MagicPacketN<N> {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl<const N: usize> From<MagicPacketN<N>> for MagicPacket {
+    fn from(packet: MagicPacketN<N>) -> Self {
+        Self(packet.0.to_vec())
+    }
+}
+
+impl<const N: usize> TryFrom<&MagicPacket> for MagicPacketN<N> {
+    type Error = WakeError;
+
+    fn try_from(packet: &MagicPacket) -> Result<Self, Self::Error> {
+        let bytes = <[u8; N]>::try_from(packet.0.as_slice()).map_err(|_| {
+            WakeError::InvalidPacket(format!("expected {N} bytes, got {}", packet.0.len()))
+        })?;
+
+        MagicPacket::try_from(bytes.as_slice())?;
+        Ok(Self(bytes))
+    }
+}
+
+impl<'a, const N: usize> From<&'a MagicPacketN<N>> for Cow<'a, [u8]> {
+    fn from(packet: &'a MagicPacketN<N>) -> Self {
+        Cow::Borrowed(&packet.0)
+    }
+}
+
+/// Represents a MAC address
+///
+/// ## Parsing
+///
+/// [`Mac::from_str`] accepts six groups of two hexadecimal digits in either case, separated by `:`, `-`, `_` or `.`, so `01:23:45:67:89:ab` and `01.23.45.67.89.AB` are the same address.
+///
+/// The separators are checked one at a time, so mixing them in one string like `01:23-45.67_89:ab` is accepted as well, this keeps inputs from tools that disagree on separators working. Whitespace and a single leading or trailing separator are ignored. Cisco's three-group form (`0123.4567.89ab`) is not accepted.
+///
+/// Use [`Mac::from_str_strict`] to reject `_`, which is unusual enough to be a typo, or [`Mac::from_str_lenient`] to also accept Unicode look-alikes of the separators.
+///
+/// ## Examples
+///
+/// ```rust
+/// use std::str::FromStr;
+/// use waker::Mac;
+///
+/// let mac = Mac::from_str("01:23:45:67:89:AB").unwrap();
+///
+/// assert_eq!(Mac::from_str("01.23.45.67.89.ab"), Ok(mac));
+/// assert_eq!(Mac::from_str("01:23-45.67_89:ab"), Ok(mac));
+/// assert!(Mac::from_str("0123.4567.89ab").is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Mac(pub [u8; 6]);
+
+impl Mac {
+    /// Validates a MAC address string without keeping the parsed [`Mac`]
+    ///
+    /// This is the canonical way to validate user input, UI layers can build their own validation result on top of it instead of duplicating the parsing rules.
+    ///
+    /// ## Arguments
+    ///
+    /// * `s` - The MAC address string to validate
+    ///
+    /// ## Errors
+    ///
+    /// Returns the same [`MacAddressError`] that [`Mac::from_str`] would return if the MAC address is invalid
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use waker::Mac;
+    ///
+    /// assert!(Mac::validate("01:23:45:67:89:AB").is_ok());
+    /// assert!(Mac::validate("01:23:45:67:89").is_err());
+    /// ```
+    pub fn validate(s: &str) -> Result<(), MacAddressError> {
+        Self::from_str(s).map(|_| ())
+    }
+
+    /// Compares the MAC address to a string, ignoring differences in case and separators
+    ///
+    /// ## Arguments
+    ///
+    /// * `s` - The MAC address string to compare to
+    ///
+    /// ## Returns
+    ///
+    /// `true` if the string parses to the same MAC address, `false` if it doesn't or if it isn't a valid MAC address
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use waker::Mac;
+    ///
+    /// let mac = Mac([0x01, 0x23, 0x45, 0x67, 0x89, 0xAB]);
+    ///
+    /// assert!(mac.eq_str("01-23-45-67-89-ab"));
+    /// assert!(!mac.eq_str("not a mac"));
+    /// ```
+    #[must_use]
+    pub fn eq_str(&self, s: &str) -> bool {
+        Self::from_str(s).is_ok_and(|mac| mac == *self)
+    }
+
+    /// Finds the first MAC address in arbitrary text, like a line of `ip link` or `ifconfig` output
+    ///
+    /// Only MAC addresses that stand on their own are returned, so parts of longer identifiers like EUI-64 addresses are ignored.
+    ///
+    /// ## Arguments
+    ///
+    /// * `text` - The text to search
+    ///
+    /// ## Returns
+    ///
+    /// The first MAC address found in the text, or [`None`] if there isn't any
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use waker::Mac;
+    ///
+    /// let mac = Mac::find_in("link/ether 01:23:45:67:89:ab brd ff:ff:ff:ff:ff:ff");
+    /// assert_eq!(mac, Some(Mac([0x01, 0x23, 0x45, 0x67, 0x89, 0xAB])));
+    /// ```
+    #[must_use]
+    pub fn find_in(text: &str) -> Option<Self> {
+        find_macs(text).next()
+    }
+
+    /// Finds all MAC addresses in arbitrary text, in the order they appear
+    ///
+    /// See [`Mac::find_in`] for the rules used to find them.
+    ///
+    /// ## Arguments
+    ///
+    /// * `text` - The text to search
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use waker::Mac;
+    ///
+    /// let macs = Mac::find_all_in("link/ether 01:23:45:67:89:ab brd ff:ff:ff:ff:ff:ff");
+    /// assert_eq!(macs, [Mac([0x01, 0x23, 0x45, 0x67, 0x89, 0xAB]), Mac([0xFF; 6])]);
+    /// ```
+    #[must_use]
+    pub fn find_all_in(text: &str) -> Vec<Self> {
+        find_macs(text).collect()
+    }
+
+    /// Formats the MAC address in lowercase colon form into a buffer on the stack, without allocating
+    ///
+    /// This is what [`Display`](fmt::Display) uses, it's useful when logging many MAC addresses.
+    ///
+    /// ## Arguments
+    ///
+    /// * `buf` - The buffer to write the MAC address into
+    ///
+    /// ## Returns
+    ///
+    /// The MAC address string, borrowed from the buffer
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use waker::Mac;
+    ///
+    /// let mac = Mac([0x01, 0x23, 0x45, 0x67, 0x89, 0xAB]);
+    /// let mut buf = [0u8; 17];
+    ///
+    /// assert_eq!(mac.to_str_buf(&mut buf), "01:23:45:67:89:ab");
+    /// ```
+    pub fn to_str_buf<'b>(&self, buf: &'b mut [u8; 17]) -> &'b str {
+        format_mac(self.0, buf, HEX_LOWER)
+    }
+
+    /// Returns `true` if the MAC address is a multicast address (bit 0 of the first octet is set), this includes the broadcast address
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use waker::Mac;
+    ///
+    /// assert!(Mac([0x01, 0x00, 0x5E, 0x00, 0x00, 0x01]).is_multicast());
+    /// assert!(!Mac([0x00, 0x1B, 0x21, 0x00, 0x00, 0x01]).is_multicast());
+    /// ```
+    #[must_use]
+    pub fn is_multicast(&self) -> bool {
+        self.0[0] & 0x01 != 0
+    }
+
+    /// Returns `true` if the MAC address is a unicast address (bit 0 of the first octet is not set)
+    ///
+    /// This is the inverse of [`Mac::is_multicast`].
+    #[must_use]
+    pub fn is_unicast(&self) -> bool {
+        !self.is_multicast()
+    }
+
+    /// Returns `true` if the MAC address is the broadcast address `ff:ff:ff:ff:ff:ff`
+    #[must_use]
+    pub fn is_broadcast(&self) -> bool {
+        self.0 == [0xFF; 6]
+    }
+
+    /// Returns `true` if the MAC address is locally administered (bit 1 of the first octet is set)
+    ///
+    /// Locally administered addresses are assigned by software instead of the manufacturer, like randomized MAC addresses on phones or the addresses of virtual machines.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use waker::Mac;
+    ///
+    /// assert!(Mac([0x02, 0x00, 0x00, 0x00, 0x00, 0x01]).is_locally_administered());
+    /// assert!(!Mac([0x00, 0x1B, 0x21, 0x00, 0x00, 0x01]).is_locally_administered());
+    /// ```
+    #[must_use]
+    pub fn is_locally_administered(&self) -> bool {
+        self.0[0] & 0x02 != 0
+    }
+
+    /// Returns `true` if the MAC address is universally administered (bit 1 of the first octet is not set), which means it was assigned by the manufacturer
+    ///
+    /// This is the inverse of [`Mac::is_locally_administered`].
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use waker::Mac;
+    ///
+    /// assert!(Mac([0x00, 0x1B, 0x21, 0x00, 0x00, 0x01]).is_universally_administered());
+    /// ```
+    #[must_use]
+    pub fn is_universally_administered(&self) -> bool {
+        !self.is_locally_administered()
+    }
+
+    /// Returns the Organizationally Unique Identifier (OUI) of the MAC address, the first three octets that identify the manufacturer
+    ///
+    /// It's only meaningful for universally administered addresses, see [`Mac::is_universally_administered`].
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use waker::Mac;
+    ///
+    /// assert_eq!(Mac([0x00, 0x1B, 0x21, 0x12, 0x34, 0x56]).oui(), [0x00, 0x1B, 0x21]);
+    /// ```
+    #[must_use]
+    pub fn oui(&self) -> [u8; 3] {
+        [self.0[0], self.0[1], self.0[2]]
+    }
+
+    /// Returns the name of the manufacturer of the network interface, looked up by its [OUI](Mac::oui) in an embedded table
+    ///
+    /// The table only covers common vendors of network interfaces, virtual machines and networking equipment, not the whole IEEE registry. Locally administered and multicast addresses always return `None` since their OUI wasn't assigned by a manufacturer.
+    ///
+    /// ## Returns
+    ///
+    /// The vendor name, or `None` if the OUI isn't in the table
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use waker::Mac;
+    ///
+    /// assert_eq!(Mac([0x00, 0x1B, 0x21, 0x12, 0x34, 0x56]).vendor(), Some("Intel"));
+    /// assert_eq!(Mac([0x02, 0x1B, 0x21, 0x12, 0x34, 0x56]).vendor(), None);
+    /// ```
+    #[cfg(feature = "oui-db")]
+    #[must_use]
+    pub fn vendor(&self) -> Option<&'static str> {
+        if self.is_locally_administered() || self.is_multicast() {
+            return None;
+        }
+
+        crate::oui::vendor(self.oui())
+    }
+
+    /// Returns a copy of the MAC address with the locally administered bit (bit 1 of the first octet) set or cleared
+    ///
+    /// ## Arguments
+    ///
+    /// * `local` - Whether the bit should be set
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use waker::Mac;
+    ///
+    /// let mac = Mac([0x00, 0x1B, 0x21, 0x12, 0x34, 0x56]).with_local_bit(true);
+    ///
+    /// assert_eq!(mac, Mac([0x02, 0x1B, 0x21, 0x12, 0x34, 0x56]));
+    /// assert!(mac.is_locally_administered());
+    /// ```
+    #[must_use]
+    pub fn with_local_bit(self, local: bool) -> Self {
+        self.with_first_octet_bit(0x02, local)
+    }
+
+    /// Returns a copy of the MAC address with the multicast bit (bit 0 of the first octet) set or cleared
+    ///
+    /// Clearing it is a way to sanitize an input into a unicast address.
+    ///
+    /// ## Arguments
+    ///
+    /// * `multicast` - Whether the bit should be set
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use waker::Mac;
+    ///
+    /// let mac = Mac([0x01, 0x00, 0x5E, 0x00, 0x00, 0xFB]).with_multicast_bit(false);
+    ///
+    /// assert_eq!(mac, Mac([0x00, 0x00, 0x5E, 0x00, 0x00, 0xFB]));
+    /// assert!(mac.is_unicast());
+    /// ```
+    #[must_use]
+    pub fn with_multicast_bit(self, multicast: bool) -> Self {
+        self.with_first_octet_bit(0x01, multicast)
+    }
+
+    /// Returns a copy of the MAC address with the bits of the mask in the first octet set or cleared
+    fn with_first_octet_bit(mut self, mask: u8, set: bool) -> Self {
+        if set {
+            self.0[0] |= mask;
+        } else {
+            self.0[0] &= !mask;
+        }
+
+        self
+    }
+}
+
+/// Length of a MAC address string like `01:23:45:67:89:AB`
+const MAC_STR_LEN: usize = 17;
+
+/// Returns an iterator over the MAC addresses that stand on their own in the text
+fn find_macs(text: &str) -> impl Iterator<Item = Mac> + '_ {
+    let is_joined =
+        |c: Option<char>| c.is_some_and(|c| c.is_ascii_alphanumeric() || c == ':' || c == '-');
+    let mut start = 0;
+
+    std::iter::from_fn(move || {
+        while start + MAC_STR_LEN <= text.len() {
+            let end = start + MAC_STR_LEN;
+            let current = start;
+            start += text[start..].chars().next().map_or(1, char::len_utf8);
+
+            if !text.is_char_boundary(end)
+                || !text[current..].starts_with(|c: char| c.is_ascii_hexdigit())
+                || is_joined(text[..current].chars().next_back())
+                || is_joined(text[end..].chars().next())
+            {
+                continue;
+            }
+
+            if let Ok(mac) = Mac::from_str(&text[current..end]) {
+                start = end;
+                return Some(mac);
+            }
+        }
+
+        None
+    })
+}
+
+impl PartialEq<str> for Mac {
+    fn eq(&self, other: &str) -> bool {
+        self.eq_str(other)
+    }
+}
+
+impl PartialEq<&str> for Mac {
+    fn eq(&self, other: &&str) -> bool {
+        self.eq_str(other)
+    }
+}
+
+impl From<[u8; 6]> for Mac {
+    fn from(value: [u8; 6]) -> Self {
+        Self(value)
+    }
+}
+
+impl TryFrom<&[u8]> for Mac {
+    type Error = MacAddressError;
+
+    fn try_from(value: &[u8]) -> std::result::Result<Self, Self::Error> {
+        if value.len() != 6 {
+            return Err(MacAddressError::InvalidLength(value.len()));
+        }
+
+        let mut bytes = [0u8; 6];
+        bytes.copy_from_slice(value);
+
+        Ok(Self(bytes))
+    }
+}
+
+impl TryFrom<&str> for Mac {
+    type Error = MacAddressError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl FromStr for Mac {
+    type Err = MacAddressError;
+
+    fn from_str(input: &str) -> std::result::Result<Self, Self::Err> {
+        Self::parse_with(input, &MAC_SEPARATORS)
+    }
+}
+
+impl Mac {
+    /// Looks up the MAC address of a machine on the local network by its IPv4 address in the ARP cache
+    ///
+    /// The cache only has machines this host talked to recently, so a machine that has been asleep for a while usually isn't there. The cache is read from `/proc/net/arp` so this is only supported on Linux.
+    ///
+    /// ## Arguments
+    ///
+    /// * `ip` - The IP address of the machine
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`WakeError::ArpLookup`] if the IP address isn't in the cache, is an IPv6 address or the cache can't be read
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use waker::{wake_device, Mac, WakeTarget};
+    ///
+    /// let mac = Mac::from_arp("192.168.1.10".parse().unwrap()).unwrap();
+    /// wake_device(WakeTarget::new(mac).wake_options()).unwrap();
+    /// ```
+    pub fn from_arp(ip: IpAddr) -> Result<Self, WakeError> {
+        crate::addr::arp_lookup(ip)
+    }
+
+    /// Parses a MAC address like [`Mac::from_str`] but without accepting `_` as a separator
+    ///
+    /// The lenient parser accepts `_` because some tools use it where `:` isn't allowed, like in file names, but it's unusual enough in MAC notation that it can also hide a typo or a value picked from the wrong place, strict callers can opt out of it with this.
+    ///
+    /// ## Arguments
+    ///
+    /// * `s` - The MAC address string, separated by `:`, `-` or `.`
+    ///
+    /// ## Errors
+    ///
+    /// Returns the same errors as [`Mac::from_str`], a `_` separator is reported as [`MacAddressError::InvalidCharAt`]
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use waker::Mac;
+    ///
+    /// assert!(Mac::from_str_strict("01:23:45:67:89:AB").is_ok());
+    /// assert!(Mac::from_str_strict("01_23_45_67_89_AB").is_err());
+    /// ```
+    pub fn from_str_strict(s: &str) -> Result<Self, MacAddressError> {
+        Self::parse_with(s, &STRICT_MAC_SEPARATORS)
+    }
+
+    /// Parses a MAC address like [`Mac::from_str`] after replacing Unicode look-alikes of the separators with their ASCII version
+    ///
+    /// Text copied from documents and web pages sometimes has typographic hyphens (like `‐` or `–`) or colons (like `꞉` or `：`) that look right but aren't accepted by [`Mac::from_str`].
+    ///
+    /// ## Arguments
+    ///
+    /// * `s` - The MAC address string
+    ///
+    /// ## Errors
+    ///
+    /// Returns the same errors as [`Mac::from_str`], the index of [`MacAddressError::InvalidCharAt`] is the one in the string after the look-alikes were replaced
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use waker::Mac;
+    ///
+    /// assert_eq!(
+    ///     Mac::from_str_lenient("01\u{2010}23\u{2010}45\u{2010}67\u{2010}89\u{2010}AB"),
+    ///     Ok(Mac([0x01, 0x23, 0x45, 0x67, 0x89, 0xAB]))
+    /// );
+    /// ```
+    pub fn from_str_lenient(s: &str) -> Result<Self, MacAddressError> {
+        let normalized: String = s
+            .chars()
+            .map(|c| match c {
+                c if COLON_LOOKALIKES.contains(&c) => ':',
+                c if HYPHEN_LOOKALIKES.contains(&c) => '-',
+                c if DOT_LOOKALIKES.contains(&c) => '.',
+                c => c,
+            })
+            .collect();
+
+        Self::parse_with(&normalized, &MAC_SEPARATORS)
+    }
+
+    /// Reads the destination and source MAC addresses from the raw bytes of an Ethernet header, like the ones in a packet capture
+    ///
+    /// Only the first 12 bytes are read, the EtherType and anything after it are ignored so the whole frame can be passed.
+    ///
+    /// ## Arguments
+    ///
+    /// * `header` - The bytes of the Ethernet frame, starting with the destination MAC address
+    ///
+    /// ## Returns
+    ///
+    /// The destination and source MAC addresses, or `None` if there are less than 12 bytes
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use waker::Mac;
+    ///
+    /// let header = [
+    ///     0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, // destination
+    ///     0x00, 0x1B, 0x21, 0x12, 0x34, 0x56, // source
+    ///     0x08, 0x42, // EtherType
+    /// ];
+    ///
+    /// assert_eq!(
+    ///     Mac::from_hexdump_bytes(&header),
+    ///     Some((Mac([0xFF; 6]), Mac([0x00, 0x1B, 0x21, 0x12, 0x34, 0x56])))
+    /// );
+    /// ```
+    #[must_use]
+    pub fn from_hexdump_bytes(header: &[u8]) -> Option<(Self, Self)> {
+        let destination = header.get(..6)?.try_into().ok()?;
+        let source = header.get(6..12)?.try_into().ok()?;
+
+        Some((Self(destination), Self(source)))
+    }
+
+    /// Parses a MAC address accepting any of the separators between the bytes
+    fn parse_with(input: &str, separators: &[char]) -> Result<Self, MacAddressError> {
+        let s = trim_mac_input(input, separators);
+        let offset = s.as_ptr() as usize - input.as_ptr() as usize;
+        let mut bytes = [0u8; 6];
+        let mut s_chars = s.char_indices().map(|(i, c)| (i + offset, c)).peekable();
+        let invalid = |(index, found)| MacAddressError::InvalidCharAt { index, found };
+
+        for (i, byte_ref) in bytes.iter_mut().enumerate() {
+            let c1 = s_chars
+                .next()
+                .ok_or(MacAddressError::InvalidLength(s.len()))?;
+            let c2 = s_chars
+                .next()
+                .ok_or(MacAddressError::InvalidLength(s.len()))?;
+
+            let hi = hex_val(c1.1).map_err(|_| invalid(c1))?;
+            let lo = hex_val(c2.1).map_err(|_| invalid(c2))?;
+            *byte_ref = (hi << 4) | lo;
+
+            if i < 5 {
+                match s_chars.next() {
+                    Some((_, c)) if separators.contains(&c) => {}
+                    Some(c) => return Err(invalid(c)),
+                    None => return Err(MacAddressError::InvalidLength(s.len())),
+                }
+            }
+        }
+
+        if s_chars.next().is_some() {
+            return Err(MacAddressError::InvalidLength(s.len()));
+        }
+
+        Ok(Self(bytes))
+    }
+}
+
+/// The characters accepted between the bytes of a MAC address string
+const MAC_SEPARATORS: [char; 4] = [':', '-', '_', '.'];
+
+/// The separators accepted by [`Mac::from_str_strict`]
+const STRICT_MAC_SEPARATORS: [char; 3] = [':', '-', '.'];
+
+/// Unicode characters that [`Mac::from_str_lenient`] reads as `:`
+const COLON_LOOKALIKES: [char; 5] = ['\u{A789}', '\u{02D0}', '\u{2236}', '\u{FE55}', '\u{FF1A}'];
+
+/// Unicode characters that [`Mac::from_str_lenient`] reads as `-`
+const HYPHEN_LOOKALIKES: [char; 8] = [
+    '\u{2010}', '\u{2011}', '\u{2012}', '\u{2013}', '\u{2014}', '\u{2212}', '\u{FE63}', '\u{FF0D}',
+];
+
+/// Unicode characters that [`Mac::from_str_lenient`] reads as `.`
+const DOT_LOOKALIKES: [char; 2] = ['\u{2024}', '\u{FF0E}'];
+
+/// Trims whitespace and a single leading or trailing separator, inputs copied from web UIs sometimes have them
+fn trim_mac_input<'s>(s: &'s str, separators: &[char]) -> &'s str {
+    let s = s.trim();
+    let s = s.strip_prefix(separators).unwrap_or(s);
+    let s = s.strip_suffix(separators).unwrap_or(s);
+
+    s.trim()
+}
+
+/// Lowercase hexadecimal digits used when formatting MAC addresses
+const HEX_LOWER: &[u8; 16] = b"0123456789abcdef";
+
+/// Uppercase hexadecimal digits used when formatting MAC addresses
+const HEX_UPPER: &[u8; 16] = b"0123456789ABCDEF";
+
+/// Writes the MAC address bytes as hexadecimal separated by `:` into the buffer using the given digits
+fn format_mac<'b>(bytes: [u8; 6], buf: &'b mut [u8; MAC_STR_LEN], digits: &[u8; 16]) -> &'b str {
+    for (i, byte) in bytes.iter().enumerate() {
+        let pos = i * 3;
+
+        buf[pos] = digits[usize::from(byte >> 4)];
+        buf[pos + 1] = digits[usize::from(byte & 0x0F)];
+
+        if i < 5 {
+            buf[pos + 2] = b':';
+        }
+    }
+
+    // only ASCII hex digits and colons are written so this is always valid UTF-8
+    std::str::from_utf8(buf).unwrap_or_default()
+}
+
+impl fmt::Display for Mac {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::LowerHex::fmt(self, f)
+    }
+}
+
+impl fmt::LowerHex for Mac {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.to_str_buf(&mut [0u8; MAC_STR_LEN]))
+    }
+}
+
+impl fmt::UpperHex for Mac {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(format_mac(self.0, &mut [0u8; MAC_STR_LEN], HEX_UPPER))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Mac {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Mac {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse::<Mac>().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Represents a hardware address that can be either a 48-bit MAC address or a 64-bit EUI-64 identifier
+///
+/// Some devices, mostly IoT ones, use EUI-64 identifiers, parsing into a [`HwAddr`] lets callers handle both widths. Only [`HwAddr::Mac48`] can be used to create a magic packet.
+///
+/// ## Examples
+///
+/// ```rust
+/// use waker::{create_magic_packet, HwAddr};
+///
+/// let mac: HwAddr = "01:23:45:67:89:AB".parse().unwrap();
+/// assert!(create_magic_packet(mac).is_ok());
+///
+/// let eui: HwAddr = "01:23:45:67:89:AB:CD:EF".parse().unwrap();
+/// assert!(create_magic_packet(eui).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HwAddr {
+    /// A 48-bit MAC address
+    Mac48([u8; 6]),
+
+    /// A 64-bit EUI-64 identifier
+    Eui64([u8; 8]),
+}
+
+impl HwAddr {
+    /// Returns the address as a [`Mac`] if it's a 48-bit MAC address
+    ///
+    /// ## Returns
+    ///
+    /// [`Some`] with the MAC address for [`HwAddr::Mac48`], [`None`] for [`HwAddr::Eui64`]
+    #[must_use]
+    pub fn as_mac(&self) -> Option<Mac> {
+        match self {
+            Self::Mac48(bytes) => Some(Mac(*bytes)),
+            Self::Eui64(_) => None,
+        }
+    }
+
+    /// Returns the bytes of the address, 6 for [`HwAddr::Mac48`] and 8 for [`HwAddr::Eui64`]
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        match self {
+            Self::Mac48(bytes) => bytes,
+            Self::Eui64(bytes) => bytes,
+        }
+    }
+}
+
+impl From<Mac> for HwAddr {
+    fn from(mac: Mac) -> Self {
+        Self::Mac48(mac.0)
+    }
+}
+
+impl TryFrom<&[u8]> for HwAddr {
+    type Error = MacAddressError;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        if let Ok(bytes) = <[u8; 6]>::try_from(value) {
+            Ok(Self::Mac48(bytes))
+        } else if let Ok(bytes) = <[u8; 8]>::try_from(value) {
+            Ok(Self::Eui64(bytes))
+        } else {
+            Err(MacAddressError::InvalidLength(value.len()))
+        }
+    }
+}
+
+impl FromStr for HwAddr {
+    type Err = MacAddressError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = trim_mac_input(s, &MAC_SEPARATORS);
+        let mut bytes = Vec::with_capacity(8);
+
+        for group in s.split(MAC_SEPARATORS) {
+            let mut chars = group.chars();
+
+            match (chars.next(), chars.next(), chars.next()) {
+                (Some(c1), Some(c2), None) => bytes.push((hex_val(c1)? << 4) | hex_val(c2)?),
+                _ => return Err(MacAddressError::InvalidMacAddress(s.to_string())),
+            }
+        }
+
+        Self::try_from(bytes.as_slice())
+            .map_err(|_| MacAddressError::InvalidMacAddress(s.to_string()))
+    }
+}
+
+impl fmt::Display for HwAddr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, byte) in self.as_bytes().iter().enumerate() {
+            if i > 0 {
+                f.write_str(":")?;
+            }
+
+            write!(f, "{byte:02x}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// How long each phase of [`wake_device_with_stats`](crate::wake_device_with_stats) took
+///
+/// All the durations are measured with [`Instant`](std::time::Instant) so `bind_time` and `send_time` are never longer than `total`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct WakeStats {
+    /// Time spent resolving the addresses and binding the UDP socket
+    pub bind_time: Duration,
+
+    /// Time spent sending the packets, the pauses between repeats are not included
+    pub send_time: Duration,
+
+    /// Time the whole call took, including the pauses between repeats
+    pub total: Duration,
+}
+
+/// A machine to wake up, bundling an optional name with its MAC address
+///
+/// This is the shared path for front-ends that keep a list of named machines, they can convert their own type into a [`WakeTarget`] and let it build the packet and options.
+///
+/// ## Examples
+///
+/// ```rust,no_run
+/// use waker::{wake_device, Mac, WakeTarget};
+///
+/// let target = WakeTarget::new(Mac([0x01, 0x23, 0x45, 0x67, 0x89, 0xAB])).with_name("nas");
+/// wake_device(target.wake_options().broadcast_address("192.168.0.255:9")).unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WakeTarget {
+    /// The name of the machine, if it has one
+    pub name: Option<String>,
+
+    /// The MAC address of the machine
+    pub mac: Mac,
+}
+
+impl From<Mac> for WakeTarget {
+    fn from(mac: Mac) -> Self {
+        Self::new(mac)
+    }
+}
+
+impl WakeTarget {
+    /// Creates a new unnamed [`WakeTarget`] for the specified MAC address
+    #[must_use]
+    pub fn new(mac: Mac) -> Self {
+        Self { name: None, mac }
+    }
+
+    /// Sets the name of the machine
+    #[must_use]
+    pub fn with_name<S>(mut self, name: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Creates the magic packet for the machine
+    #[must_use]
+    pub fn packet(&self) -> MagicPacket {
+        MagicPacket::for_mac(self.mac)
+    }
+
+    /// Creates [`WakeOptions`] that own the magic packet for the machine, with the default addresses
+    #[must_use]
+    pub fn wake_options(&self) -> WakeOptions<'static> {
+        WakeOptions::new(self.packet())
+    }
+}
+
+/// Wake-on-LAN options
+///
+/// Create them with [`WakeOptions::new`] or one of the `From` conversions and set the options with the builder methods, the struct is `#[non_exhaustive]` so new options can be added without breaking code that uses it.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct WakeOptions<'a> {
+    /// The packet to send, you can create one with [`create_magic_packet`](crate::create_magic_packet)
+    ///
+    /// It can also borrow the bytes of a packet that is already in memory (e.g. received from the network) so nothing is copied before sending
+    pub packet: Cow<'a, [u8]>,
+
+    /// The broadcast address to send the magic packet to
+    ///
+    /// Defaults to `"255.255.255.255:9"`
+    pub broadcast_address: Cow<'a, str>,
+
+    /// The address to bind the UDP socket to, either as `IP:PORT` or just the IP of the interface to send from (an ephemeral port is used)
+    ///
+    /// On hosts with multiple network interfaces this selects which one the packet leaves from, `0.0.0.0` lets the OS choose.
+    ///
+    /// Defaults to `"0.0.0.0:0"`
+    pub bind_address: Cow<'a, str>,
+
+    /// The SecureOn password sent right after the packet, see [`WakeOptions::secure_on`]
+    ///
+    /// Defaults to [`None`]
+    pub pass: Option<[u8; 6]>,
+
+    /// Whether to return an error instead of sending when the options are likely misconfigured, see [`WakeOptions::check_bind`]
+    ///
+    /// Defaults to `false`
+    pub strict: bool,
+
+    /// Whether to log the target MAC address, length and destination of the packet right before sending it, requires the `log` feature
+    ///
+    /// Defaults to `false`
+    pub log_packet: bool,
+
+    /// Whether to do all the validation and socket setup of [`wake_device`](crate::wake_device) but skip sending the packet
+    ///
+    /// Defaults to `false`
+    pub dry_run: bool,
+
+    /// Raw bytes sent after the magic packet, for devices that expect a vendor-specific trailer
+    ///
+    /// Defaults to `None`
+    pub trailer: Option<Cow<'a, [u8]>>,
+
+    /// The address of a SOCKS5 proxy to send the packets through with UDP ASSOCIATE (in `IP:PORT` format), requires the `socks` feature
+    ///
+    /// Defaults to `None`
+    #[cfg(feature = "socks")]
+    pub proxy: Option<Cow<'a, str>>,
+
+    /// The last known IP address of the machine, if set the packet is also sent directly to it on the same port as the broadcast
+    ///
+    /// Defaults to `None`
+    pub unicast: Option<IpAddr>,
+
+    /// The address of a relay on the target network, if set the packet is sent to it instead of the broadcast address (in `IP:PORT` format)
+    ///
+    /// Defaults to `None`
+    pub relay: Option<Cow<'a, str>>,
+
+    /// How many times to send the packet, `0` is treated as `1`
+    ///
+    /// Defaults to `1`
+    pub repeat: usize,
+
+    /// The highest [`WakeOptions::repeat`] that is accepted
+    ///
+    /// Defaults to [`MAX_REPEAT`](crate::MAX_REPEAT)
+    pub max_repeat: usize,
+
+    /// How long to wait between sends when [`WakeOptions::repeat`] is more than `1`
+    ///
+    /// Defaults to no delay
+    pub interval: Duration,
+
+    /// The maximum random delay added to [`WakeOptions::interval`] between sends
+    ///
+    /// Defaults to no jitter
+    pub jitter: Duration,
+
+    /// Called after each successful send, see [`WakeOptions::on_sent`]
+    on_sent: Option<SentCallback<'a>>,
+}
+
+/// Signature of the callback passed to [`WakeOptions::on_sent`]
+type SentFn<'a> = dyn Fn(&Mac, &SocketAddr) + Send + Sync + 'a;
+
+/// The callback set with [`WakeOptions::on_sent`], shared so the options stay cheap to clone
+#[derive(Clone)]
+struct SentCallback<'a>(Arc<SentFn<'a>>);
+
+impl fmt::Debug for SentCallback<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SentCallback")
+    }
+}
+
+impl<'a> From<&'a MagicPacket> for WakeOptions<'a> {
+    fn from(packet: &'a MagicPacket) -> Self {
+        Self::new(packet)
+    }
+}
+
+impl<'a> From<&'a PatternPacket> for WakeOptions<'a> {
+    fn from(packet: &'a PatternPacket) -> Self {
+        Self::new(packet)
+    }
+}
+
+impl<'a> From<&'a [u8]> for WakeOptions<'a> {
+    fn from(packet: &'a [u8]) -> Self {
+        Self::new(packet)
+    }
+}
+
+impl<'a, const N: usize> From<&'a [u8; N]> for WakeOptions<'a> {
+    fn from(packet: &'a [u8; N]) -> Self {
+        Self::new(packet)
+    }
+}
+
+impl<'a, const N: usize> From<&'a MagicPacketN<N>> for WakeOptions<'a> {
+    fn from(packet: &'a MagicPacketN<N>) -> Self {
+        Self::new(packet)
+    }
+}
+
+impl<'a> WakeOptions<'a> {
+    /// Creates a new [`WakeOptions`] with the specified magic packet
+    ///
+    /// The packet can be a borrowed or owned [`MagicPacket`], or the raw bytes of one as a byte slice, byte array or [`Vec<u8>`]
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use waker::{create_magic_packet, WakeOptions};
+    ///
+    /// let packet = create_magic_packet("01:23:45:67:89:AB").unwrap();
+    /// let _ = WakeOptions::new(&packet);
+    ///
+    /// // borrows the bytes without copying them
+    /// let bytes: &[u8] = &packet.0;
+    /// let _ = WakeOptions::new(bytes);
+    /// ```
+    #[must_use]
+    pub fn new<P>(magic_packet: P) -> Self
+    where
+        P: Into<Cow<'a, [u8]>>,
+    {
+        Self {
+            packet: magic_packet.into(),
+            broadcast_address: "255.255.255.255:9".into(),
+            bind_address: "0.0.0.0:0".into(),
+            pass: None,
+            strict: false,
+            log_packet: false,
+            dry_run: false,
+            trailer: None,
+            #[cfg(feature = "socks")]
+            proxy: None,
+            unicast: None,
+            relay: None,
+            repeat: 1,
+            max_repeat: crate::MAX_REPEAT,
+            interval: Duration::ZERO,
+            jitter: Duration::ZERO,
+            on_sent: None,
+        }
+    }
+
+    /// Creates a new [`WakeOptions`] with the specified magic packet from a `wol://` URL
+    ///
+    /// The host of the URL is the broadcast address and the query can set other options, so a whole wake configuration can be shared as a single string.
+    ///
+    /// Supported query parameters:
+    ///
+    /// * `bind` - The bind address, see [`WakeOptions::bind_address`]
+    /// * `unicast` - The last known IP of the machine, see [`WakeOptions::also_unicast`]
+    /// * `relay` - The address of a relay, see [`WakeOptions::relay`]
+    /// * `repeat` - How many times to send the packet, see [`WakeOptions::repeat`]
+    /// * `strict` - `true` or `false`, see [`WakeOptions::strict`]
+    ///
+    /// ## Arguments
+    ///
+    /// * `url` - The URL, like `wol://192.168.1.255:9?bind=0.0.0.0:0`
+    /// * `magic_packet` - The magic packet, like in [`WakeOptions::new`]
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`WakeError::InvalidUrl`] if the URL doesn't use the `wol` scheme, has an unknown query parameter or an invalid value, or [`WakeError::InvalidAddress`] if the broadcast or bind address can't be resolved
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use waker::{create_magic_packet, WakeOptions};
+    ///
+    /// let packet = create_magic_packet("01:23:45:67:89:AB").unwrap();
+    /// let options = WakeOptions::from_url("wol://192.168.1.255:9?repeat=3&bind=0.0.0.0:0&strict=true", &packet).unwrap();
+    ///
+    /// assert_eq!(options.broadcast_address, "192.168.1.255:9");
+    /// assert_eq!(options.repeat, 3);
+    /// assert!(options.strict);
+    /// ```
+    pub fn from_url<P>(url: &str, magic_packet: P) -> Result<Self, WakeError>
+    where
+        P: Into<Cow<'a, [u8]>>,
+    {
+        let invalid = |reason: String| WakeError::InvalidUrl(format!("{url}: {reason}"));
+
+        let rest = url
+            .get(..6)
+            .filter(|scheme| scheme.eq_ignore_ascii_case("wol://"))
+            .map(|_| &url[6..])
+            .ok_or_else(|| invalid("expected the `wol://` scheme".into()))?;
+        let (host, query) = rest.split_once('?').unwrap_or((rest, ""));
+        let host = host.trim_end_matches('/');
+
+        if host.is_empty() {
+            return Err(invalid("missing broadcast address".into()));
+        }
+
+        let mut options = Self::new(magic_packet).broadcast_address(host.to_string());
+
+        for param in query.split('&').filter(|param| !param.is_empty()) {
+            let (key, value) = param
+                .split_once('=')
+                .ok_or_else(|| invalid(format!("missing value for `{param}`")))?;
+
+            match key {
+                "bind" => options.bind_address = value.to_string().into(),
+                "unicast" => {
+                    let ip = value
+                        .parse::<IpAddr>()
+                        .map_err(|_| invalid(format!("invalid unicast IP `{value}`")))?;
+                    options.unicast = Some(ip);
+                }
+                "relay" => options.relay = Some(value.to_string().into()),
+                "repeat" => {
+                    options.repeat = value
+                        .parse()
+                        .map_err(|_| invalid(format!("invalid value for repeat `{value}`")))?;
+                }
+                "strict" => {
+                    options.strict = value
+                        .parse()
+                        .map_err(|_| invalid(format!("invalid value for strict `{value}`")))?;
+                }
+                _ => return Err(invalid(format!("unknown parameter `{key}`"))),
+            }
+        }
+
+        options.resolved_broadcast()?;
+        options.resolved_bind()?;
+
+        Ok(options)
+    }
+
+    /// Sets the broadcast address
+    ///
+    /// Defaults to `"255.255.255.255:9"`
+    #[must_use]
+    pub fn broadcast_address<S>(mut self, address: S) -> Self
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.broadcast_address = address.into();
+        self
+    }
+
+    /// Sets an IPv4 broadcast address from its parts, so there is no string to get wrong
+    ///
+    /// ## Arguments
+    ///
+    /// * `ip` - The broadcast IP, like `255.255.255.255` or the directed broadcast of a subnet
+    /// * `port` - The port to send the magic packet to, usually `9` or `7`
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use std::net::{Ipv4Addr, SocketAddr};
+    /// use waker::{create_magic_packet, WakeOptions};
+    ///
+    /// let packet = create_magic_packet("01:23:45:67:89:AB").unwrap();
+    /// let options = WakeOptions::new(&packet).broadcast_v4(Ipv4Addr::new(192, 168, 1, 255), 9);
+    ///
+    /// assert_eq!(options.resolved_broadcast(), Ok("192.168.1.255:9".parse::<SocketAddr>().unwrap()));
+    /// ```
+    #[must_use]
+    pub fn broadcast_v4(self, ip: Ipv4Addr, port: u16) -> Self {
+        self.broadcast_address(SocketAddrV4::new(ip, port).to_string())
+    }
+
+    /// Sets an IPv6 address to send the magic packet to from its parts, like the all-nodes multicast address `ff02::1`
+    ///
+    /// The default bind address `0.0.0.0:0` is swapped for `[::]:0` when sending to an IPv6 address, a specific [`WakeOptions::bind_address`] has to be an IPv6 address too.
+    ///
+    /// ## Arguments
+    ///
+    /// * `ip` - The IPv6 address to send the magic packet to
+    /// * `port` - The port to send the magic packet to, usually `9` or `7`
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use std::net::{Ipv6Addr, SocketAddr};
+    /// use waker::{create_magic_packet, WakeOptions};
+    ///
+    /// let packet = create_magic_packet("01:23:45:67:89:AB").unwrap();
+    /// let options = WakeOptions::new(&packet)
+    ///     .broadcast_v6(Ipv6Addr::new(0xff02, 0, 0, 0, 0, 0, 0, 1), 9)
+    ///     .bind_address("[::]:0");
+    ///
+    /// assert_eq!(options.resolved_broadcast(), Ok("[ff02::1]:9".parse::<SocketAddr>().unwrap()));
+    /// ```
+    #[must_use]
+    pub fn broadcast_v6(self, ip: Ipv6Addr, port: u16) -> Self {
+        self.broadcast_address(SocketAddrV6::new(ip, port, 0, 0).to_string())
+    }
+
+    /// Sets the broadcast address to the directed broadcast of the interface with the default route, see [`default_broadcast_addr`](crate::addr::default_broadcast_addr)
+    ///
+    /// Falls back to the limited broadcast `255.255.255.255:9` if it can't be determined.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use waker::{create_magic_packet, wake_device, WakeOptions};
+    ///
+    /// let packet = create_magic_packet("01:23:45:67:89:AB").unwrap();
+    /// wake_device(WakeOptions::new(&packet).default_broadcast()).unwrap();
+    /// ```
+    #[must_use]
+    pub fn default_broadcast(mut self) -> Self {
+        self.broadcast_address = crate::addr::default_broadcast_addr().map_or_else(
+            |_| "255.255.255.255:9".into(),
+            |addr| addr.to_string().into(),
+        );
+        self
+    }
+
+    /// Sets the bind address, either as `IP:PORT` or just the IP of the interface to send from (an ephemeral port is used)
+    ///
+    /// On hosts with multiple network interfaces (e.g. `192.168.1.x` and `10.0.0.x`) a broadcast may only leave through one of them, binding to the IP of a specific interface makes the packet leave from it. `0.0.0.0` lets the OS choose.
+    ///
+    /// Defaults to `"0.0.0.0:0"`
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use waker::{create_magic_packet, WakeOptions};
+    ///
+    /// let packet = create_magic_packet("01:23:45:67:89:AB").unwrap();
+    /// let _ = WakeOptions::new(&packet).bind_address("192.168.1.10");
+    /// ```
+    #[must_use]
+    pub fn bind_address<S>(mut self, address: S) -> Self
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.bind_address = address.into();
+        self
+    }
+
+    /// Sets whether misconfigured options should make [`wake_device`](crate::wake_device) fail instead of sending
+    ///
+    /// When disabled the packet is still sent and a failed [`WakeOptions::check_bind`] is logged as a warning, which requires the `log` feature.
+    ///
+    /// Defaults to `false`
+    #[must_use]
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Also sends the packet directly to the last known IP address of the machine, on the same port as the broadcast
+    ///
+    /// Some devices only wake up with a unicast packet while others need a broadcast, sending both maximizes the chance of waking heterogeneous hardware.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use std::net::Ipv4Addr;
+    /// use waker::{create_magic_packet, wake_device, WakeOptions};
+    ///
+    /// let packet = create_magic_packet("01:23:45:67:89:AB").unwrap();
+    /// wake_device(WakeOptions::new(&packet).also_unicast(Ipv4Addr::new(192, 168, 0, 10))).unwrap();
+    /// ```
+    #[must_use]
+    pub fn also_unicast<I>(mut self, ip: I) -> Self
+    where
+        I: Into<IpAddr>,
+    {
+        self.unicast = Some(ip.into());
+        self
+    }
+
+    /// Sends the packet to a relay on the target network instead, which broadcasts it to the broadcast address
+    ///
+    /// Nothing is broadcast from this host and [`WakeOptions::also_unicast`] is ignored, see the [`relay`](crate::relay) module for the message format and the receiving side.
+    ///
+    /// ## Arguments
+    ///
+    /// * `address` - The address of the relay (in `IP:PORT` format)
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use waker::{create_magic_packet, wake_device, WakeOptions};
+    ///
+    /// let packet = create_magic_packet("01:23:45:67:89:AB").unwrap();
+    /// wake_device(WakeOptions::new(&packet).broadcast_address("192.168.1.255:9").relay("203.0.113.10:9009")).unwrap();
+    /// ```
+    #[must_use]
+    pub fn relay<S>(mut self, address: S) -> Self
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.relay = Some(address.into());
+        self
+    }
+
+    /// Sets how many times to send the packet, a single UDP packet is easily dropped on unreliable networks
+    ///
+    /// A count of `0` is treated as `1`, sending stops at the first error.
+    ///
+    /// Defaults to `1`
+    #[must_use]
+    pub fn repeat(mut self, count: usize) -> Self {
+        self.repeat = count;
+        self
+    }
+
+    /// Sets how long to wait between sends when [`WakeOptions::repeat`] is more than `1`, nothing is waited after the last one
+    ///
+    /// Defaults to no delay
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use std::time::Duration;
+    /// use waker::{create_magic_packet, wake_device, WakeOptions};
+    ///
+    /// let packet = create_magic_packet("01:23:45:67:89:AB").unwrap();
+    /// wake_device(
+    ///     WakeOptions::new(&packet)
+    ///         .repeat(3)
+    ///         .interval(Duration::from_millis(200)),
+    /// )
+    /// .unwrap();
+    /// ```
+    #[must_use]
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Sets the highest repeat count that is accepted, sending fails with [`WakeError::RepeatTooLarge`] above it
+    ///
+    /// Defaults to [`MAX_REPEAT`](crate::MAX_REPEAT)
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use waker::{create_magic_packet, wake_device, WakeOptions};
+    ///
+    /// let packet = create_magic_packet("01:23:45:67:89:AB").unwrap();
+    /// wake_device(WakeOptions::new(&packet).repeat(500).max_repeat(1000)).unwrap();
+    /// ```
+    #[must_use]
+    pub fn max_repeat(mut self, max: usize) -> Self {
+        self.max_repeat = max;
+        self
+    }
+
+    /// Randomizes the delay between repeated sends to somewhere between `base` and `base + jitter`
+    ///
+    /// This avoids synchronized sends when waves of packets are sent from multiple hosts, it has no effect unless [`WakeOptions::repeat`] is more than `1`.
+    ///
+    /// ## Arguments
+    ///
+    /// * `base` - The minimum delay between sends
+    /// * `jitter` - The maximum random delay added to `base`
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use std::time::Duration;
+    /// use waker::{create_magic_packet, wake_device, WakeOptions};
+    ///
+    /// let packet = create_magic_packet("01:23:45:67:89:AB").unwrap();
+    /// wake_device(
+    ///     WakeOptions::new(&packet)
+    ///         .repeat(5)
+    ///         .repeat_jitter(Duration::from_millis(100), Duration::from_millis(400)),
+    /// )
+    /// .unwrap();
+    /// ```
+    #[must_use]
+    pub fn repeat_jitter(mut self, base: Duration, jitter: Duration) -> Self {
+        self.interval = base;
+        self.jitter = jitter;
+        self
+    }
+
+    /// Sets whether to log the target MAC address, length and destination of the packet right before sending it
+    ///
+    /// The record is emitted at the `debug` level through the [`log`](https://crates.io/crates/log) crate, so it requires the `log` feature and a logger to be installed, otherwise this does nothing. The destination is the relay address when [`WakeOptions::relay`] is set, and the record is marked as a dry run when [`WakeOptions::dry_run`] is on.
+    ///
+    /// Defaults to `false`
+    #[must_use]
+    pub fn log_packet(mut self, log_packet: bool) -> Self {
+        self.log_packet = log_packet;
+        self
+    }
+
+    /// Sets raw bytes to send after the magic packet, including after the SecureOn password if there is one
+    ///
+    /// Some non-standard devices expect vendor-specific bytes at the end, the packet itself is left untouched. Relays only forward the magic packet so a trailer can't be combined with [`WakeOptions::relay`].
+    ///
+    /// ## Arguments
+    ///
+    /// * `bytes` - The bytes to append
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use waker::{create_magic_packet, wake_device, WakeOptions};
+    ///
+    /// let packet = create_magic_packet("01:23:45:67:89:AB").unwrap();
+    /// wake_device(WakeOptions::new(&packet).trailer(b"\x00\x01".as_slice())).unwrap();
+    /// ```
+    #[must_use]
+    pub fn trailer<T>(mut self, bytes: T) -> Self
+    where
+        T: Into<Cow<'a, [u8]>>,
+    {
+        self.trailer = Some(bytes.into());
+        self
+    }
+
+    /// Sets a SOCKS5 proxy to send the packets through, for networks that are only reachable through a proxy like over some VPNs
+    ///
+    /// The proxy has to support the UDP ASSOCIATE command without authentication, the packets are sent to the relay it returns and it forwards them to the broadcast address.
+    ///
+    /// ## Arguments
+    ///
+    /// * `address` - The address of the proxy in `IP:PORT` format
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use waker::{create_magic_packet, wake_device, WakeOptions};
+    ///
+    /// let packet = create_magic_packet("01:23:45:67:89:AB").unwrap();
+    /// wake_device(
+    ///     WakeOptions::new(&packet)
+    ///         .broadcast_address("10.8.0.255:9")
+    ///         .proxy("127.0.0.1:1080"),
+    /// )
+    /// .unwrap();
+    /// ```
+    #[cfg(feature = "socks")]
+    #[must_use]
+    pub fn proxy<S>(mut self, address: S) -> Self
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.proxy = Some(address.into());
+        self
+    }
+
+    /// Sets whether to skip sending the packet, [`wake_device`](crate::wake_device) still resolves the addresses, binds the socket and runs the [`WakeOptions::strict`] checks
+    ///
+    /// Useful to test the whole path without sending traffic, there is no delay between repeats since nothing is sent.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use waker::{create_magic_packet, wake_device, WakeOptions};
+    ///
+    /// let packet = create_magic_packet("01:23:45:67:89:AB").unwrap();
+    ///
+    /// assert!(wake_device(WakeOptions::new(&packet).dry_run(true)).is_ok());
+    /// assert!(wake_device(WakeOptions::new(&packet).broadcast_address("nowhere").dry_run(true)).is_err());
+    /// ```
+    #[must_use]
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Sets a callback that is called with the target MAC address and the destination after each successful send
+    ///
+    /// It's called once per packet that left the socket, so repeats and [`WakeOptions::also_unicast`] call it more than once and a dry run never calls it. With a relay the destination is the relay address. Packets that don't start with the [`SYNC_STREAM`](crate::SYNC_STREAM), like the ones from [`create_pattern_packet`](crate::create_pattern_packet), don't call it.
+    ///
+    /// ## Arguments
+    ///
+    /// * `callback` - The function to call with the MAC address and destination address
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use waker::{create_magic_packet, wake_device, WakeOptions};
+    ///
+    /// let packet = create_magic_packet("01:23:45:67:89:AB").unwrap();
+    /// wake_device(WakeOptions::new(&packet).on_sent(|mac, addr| println!("Woke {mac} through {addr}"))).unwrap();
+    /// ```
+    #[must_use]
+    pub fn on_sent<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&Mac, &SocketAddr) + Send + Sync + 'a,
+    {
+        self.on_sent = Some(SentCallback(Arc::new(callback)));
+        self
+    }
+
+    /// Calls the [`WakeOptions::on_sent`] callback if there is one and the packet is a magic packet
+    pub(crate) fn notify_sent(&self, packet: &[u8], destination: SocketAddr) {
+        let Some(callback) = &self.on_sent else {
+            return;
+        };
+
+        if let Some(mac) = packet_mac(packet).filter(|_| packet.starts_with(&crate::SYNC_STREAM)) {
+            (callback.0)(&mac, &destination);
+        }
+    }
+
+    /// Returns how many bytes [`wake_device`](crate::wake_device) sends to the broadcast address with these options, useful to size receive buffers
+    ///
+    /// That's the packet, which is 102 bytes or 108 with a SecureOn password like the ones from [`MagicPacketN::with_password`], followed by the [`WakeOptions::secure_on`] password and the [`WakeOptions::trailer`] if there are any.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use waker::{Mac, MagicPacket, MagicPacketN, WakeOptions};
+    ///
+    /// let mac = Mac([0x01, 0x23, 0x45, 0x67, 0x89, 0xAB]);
+    /// let packet = MagicPacket::for_mac(mac);
+    /// let secure_on = MagicPacketN::with_password(mac, [0x11; 6]);
+    ///
+    /// assert_eq!(WakeOptions::new(&packet).expected_packet_len(), 102);
+    /// assert_eq!(WakeOptions::new(&secure_on).expected_packet_len(), 108);
+    /// assert_eq!(WakeOptions::new(&packet).secure_on([0x11; 6]).expected_packet_len(), 108);
+    /// assert_eq!(WakeOptions::new(&packet).trailer(b"\x00\x01".as_slice()).expected_packet_len(), 104);
+    /// ```
+    #[must_use]
+    pub fn expected_packet_len(&self) -> usize {
+        self.packet.len()
+            + self.pass.map_or(0, |password| password.len())
+            + self.trailer.as_ref().map_or(0, |trailer| trailer.len())
+    }
+
+    /// Resolves the broadcast address to the socket address the packet will be sent to, without sending anything
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`WakeError::InvalidAddress`] if the broadcast address can't be parsed or resolved
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use std::net::SocketAddr;
+    /// use waker::{create_magic_packet, WakeOptions};
+    ///
+    /// let packet = create_magic_packet("01:23:45:67:89:AB").unwrap();
+    /// let addr = WakeOptions::new(&packet).resolved_broadcast().unwrap();
+    ///
+    /// assert_eq!(addr, "255.255.255.255:9".parse::<SocketAddr>().unwrap());
+    /// ```
+    pub fn resolved_broadcast(&self) -> Result<SocketAddr, WakeError> {
+        resolve_address(&self.broadcast_address)
+    }
+
+    /// Resolves the bind address to the socket address the UDP socket will be bound to, without binding it
+    ///
+    /// A bind address that is just an IP resolves to port `0`, which means the OS picks an ephemeral port when binding.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`WakeError::InvalidAddress`] if the bind address can't be parsed or resolved
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use std::net::SocketAddr;
+    /// use waker::{create_magic_packet, WakeOptions};
+    ///
+    /// let packet = create_magic_packet("01:23:45:67:89:AB").unwrap();
+    /// let addr = WakeOptions::new(&packet).bind_address("192.168.1.10").resolved_bind().unwrap();
+    ///
+    /// assert_eq!(addr, "192.168.1.10:0".parse::<SocketAddr>().unwrap());
+    /// ```
+    pub fn resolved_bind(&self) -> Result<SocketAddr, WakeError> {
+        match self.bind_address.parse::<IpAddr>() {
+            Ok(ip) => Ok(SocketAddr::new(ip, 0)),
+            Err(_) => resolve_address(&self.bind_address),
+        }
+    }
+
+    /// Checks that the bind address can actually reach the broadcast address
+    ///
+    /// Binding to a loopback address like `127.0.0.1:0` while sending to a non-loopback broadcast address like `255.255.255.255:9` means the magic packet never leaves the host, this is what [`WakeOptions::strict`] guards against.
+    ///
+    /// Addresses that cannot be resolved are not checked here, they fail when sending instead.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`WakeError::LoopbackBind`] if the bind address is a loopback address and the broadcast address is not
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use waker::{create_magic_packet, WakeOptions};
+    ///
+    /// let packet = create_magic_packet("01:23:45:67:89:AB").unwrap();
+    ///
+    /// assert!(WakeOptions::new(&packet).check_bind().is_ok());
+    /// assert!(WakeOptions::new(&packet).bind_address("127.0.0.1:0").check_bind().is_err());
+    /// ```
+    pub fn check_bind(&self) -> Result<(), WakeError> {
+        let (Ok(bind), Ok(broadcast)) = (self.resolved_bind(), self.resolved_broadcast()) else {
+            return Ok(());
+        };
+
+        if bind.ip().is_loopback() && !broadcast.ip().is_loopback() {
+            return Err(WakeError::LoopbackBind { bind, broadcast });
+        }
+
+        Ok(())
+    }
+
+    /// Checks that a relay would accept what is sent to it, a relay only forwards a magic packet with an optional SecureOn password
+    ///
+    /// The relay rejects anything else without telling the sender since the UDP send itself succeeds, so it's caught before sending instead.
+    pub(crate) fn check_relay_payload(&self) -> Result<(), WakeError> {
+        if self.relay.is_none() {
+            return Ok(());
+        }
+
+        if self
+            .trailer
+            .as_ref()
+            .is_some_and(|trailer| !trailer.is_empty())
+        {
+            return Err(WakeError::UnrelayablePacket(
+                "trailers are not relayed".into(),
+            ));
+        }
+
+        let len = self.packet.len() + self.pass.map_or(0, |password| password.len());
+        if !matches!(len.checked_sub(PACKET_LEN), Some(0 | 4 | 6)) {
+            return Err(WakeError::UnrelayablePacket(format!(
+                "expected {PACKET_LEN} bytes and an optional 4 or 6-byte SecureOn password, got {len}"
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Checks every option that can fail when sending and returns all the problems at once, instead of only the first one like [`wake_device`](crate::wake_device)
+    ///
+    /// The broadcast, bind, relay and proxy addresses are resolved, a relayed packet is checked to be one the relay accepts, the repeat count is compared with [`WakeOptions::max_repeat`] and [`WakeOptions::check_bind`] is run when [`WakeOptions::strict`] is enabled. Nothing is bound or sent.
+    ///
+    /// ## Errors
+    ///
+    /// Returns every [`WakeError`] that was found, in the order the options are listed above
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use waker::{create_magic_packet, WakeOptions};
+    ///
+    /// let packet = create_magic_packet("01:23:45:67:89:AB").unwrap();
+    /// let options = WakeOptions::new(&packet)
+    ///     .broadcast_address("not an address")
+    ///     .bind_address("also not an address");
+    ///
+    /// assert_eq!(options.validate().unwrap_err().len(), 2);
+    /// ```
+    pub fn validate(&self) -> Result<(), Vec<WakeError>> {
+        let mut errors = Vec::new();
+
+        if let Err(e) = self.resolved_broadcast() {
+            errors.push(e);
+        }
+
+        if let Err(e) = self.resolved_bind() {
+            errors.push(e);
+        }
+
+        if let Some(Err(e)) = self.relay.as_deref().map(resolve_address) {
+            errors.push(e);
+        }
+
+        if let Err(e) = self.check_relay_payload() {
+            errors.push(e);
+        }
+
+        #[cfg(feature = "socks")]
+        if let Some(Err(e)) = self.proxy.as_deref().map(resolve_address) {
+            errors.push(e);
+        }
+
+        if self.repeat > self.max_repeat {
+            errors.push(WakeError::RepeatTooLarge {
+                count: self.repeat,
+                max: self.max_repeat,
+            });
+        }
+
+        if self.strict {
+            if let Err(e) = self.check_bind() {
+                errors.push(e);
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Sets the SecureOn password, which is sent right after the packet and before the [`WakeOptions::trailer`], making a magic packet 108 bytes
+    ///
+    /// Devices with SecureOn enabled ignore magic packets without the right password. Packets that already have one, like the ones from [`MagicPacketN::with_password`], are sent as they are with this password after them, so don't set both.
+    ///
+    /// ## Arguments
+    ///
+    /// * `password` - The 6-byte password, see [`parse_secure_on_password`](crate::parse_secure_on_password) to parse it from the usual `xx:xx:xx:xx:xx:xx` form
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use waker::{create_magic_packet, parse_secure_on_password, wake_device, WakeOptions};
+    ///
+    /// let packet = create_magic_packet("01:23:45:67:89:AB").unwrap();
+    /// let password = parse_secure_on_password("11:22:33:44:55:66").unwrap();
+    ///
+    /// wake_device(WakeOptions::new(&packet).secure_on(password)).unwrap();
+    /// ```
+    #[must_use]
+    pub fn secure_on(mut self, password: [u8; 6]) -> Self {
+        self.pass = Some(password);
+        self
+    }
+}
+
+/// Returns the number (starting at `1`) of the first MAC address repetition that is missing or different from the first one, if there is one
+fn mismatched_repetition(packet: &[u8]) -> Option<usize> {
+    let Some(first) = packet.get(6..12) else {
+        return Some(1);
+    };
+
+    (2..=16).find(|block| packet.get(6 * block..6 * block + 6) != Some(first))
+}
+
+/// Returns the MAC address of the first repetition in the packet bytes, if there is one
+pub(crate) fn packet_mac(packet: &[u8]) -> Option<Mac> {
+    packet
+        .get(6..12)
+        .and_then(|bytes| Mac::try_from(bytes).ok())
+}
+
+/// Resolves an address string to its first socket address
+pub(crate) fn resolve_address(address: &str) -> Result<SocketAddr, WakeError> {
+    address
+        .to_socket_addrs()
+        .map_err(|e| WakeError::InvalidAddress(format!("{address} ({e})")))?
+        .next()
+        .ok_or_else(|| WakeError::InvalidAddress(address.to_string()))
 }
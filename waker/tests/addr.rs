@@ -0,0 +1,148 @@
+// Copyright (C) 2025 DarkCeptor44
+//
+// This file is part of waker.
+//
+// waker is free software: you can redistribute it and/or modify
+// it under theterms of the GNU Lesser General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// waker is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Lesser General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public License
+// along with waker.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use waker::{
+    addr::{
+        broadcast_kind, default_broadcast_addr, is_directed_broadcast, is_limited_broadcast,
+        route_table_broadcast, BroadcastKind,
+    },
+    create_magic_packet, Mac, WakeError, WakeOptions,
+};
+
+#[test]
+fn test_broadcast_kind_limited() {
+    assert_eq!(broadcast_kind(Ipv4Addr::BROADCAST), BroadcastKind::Limited);
+    assert!(is_limited_broadcast(Ipv4Addr::BROADCAST));
+    assert!(!is_directed_broadcast(Ipv4Addr::BROADCAST));
+}
+
+#[test]
+fn test_broadcast_kind_directed() {
+    let addr: IpAddr = "192.168.1.255".parse().unwrap();
+
+    assert_eq!(broadcast_kind(addr), BroadcastKind::Directed);
+    assert!(is_directed_broadcast(addr));
+    assert!(!is_limited_broadcast(addr));
+    assert_eq!(
+        format!("sending {} to {addr}", broadcast_kind(addr)),
+        "sending directed broadcast to 192.168.1.255"
+    );
+}
+
+#[test]
+fn test_broadcast_kind_not_broadcast() {
+    assert_eq!(
+        broadcast_kind(Ipv4Addr::new(192, 168, 1, 10)),
+        BroadcastKind::NotBroadcast
+    );
+    assert_eq!(
+        broadcast_kind(Ipv4Addr::new(239, 255, 255, 255)),
+        BroadcastKind::NotBroadcast
+    );
+    assert_eq!(
+        broadcast_kind(Ipv6Addr::LOCALHOST),
+        BroadcastKind::NotBroadcast
+    );
+}
+
+#[test]
+fn test_default_broadcast_addr() {
+    match default_broadcast_addr() {
+        Ok(addr) => {
+            assert!(addr.is_ipv4());
+            assert_ne!(broadcast_kind(addr.ip()), BroadcastKind::NotBroadcast);
+            assert_eq!(addr.port(), 9);
+        }
+        Err(e) => {
+            assert!(matches!(e, WakeError::NoDefaultBroadcast(_)));
+            assert!(!e.to_string().is_empty());
+        }
+    }
+}
+
+/// Formats a line of `/proc/net/route`, the addresses are hexadecimal in host byte order
+fn route(
+    iface: &str,
+    destination: [u8; 4],
+    gateway: [u8; 4],
+    metric: u32,
+    mask: [u8; 4],
+) -> String {
+    let hex = |ip: [u8; 4]| format!("{:08X}", u32::from_ne_bytes(ip));
+
+    format!(
+        "{iface}\t{}\t{}\t0003\t0\t0\t{metric}\t{}\t0\t0\t0",
+        hex(destination),
+        hex(gateway),
+        hex(mask)
+    )
+}
+
+#[test]
+fn test_route_table_broadcast() {
+    let header = "Iface\tDestination\tGateway\tFlags\tRefCnt\tUse\tMetric\tMask\tMTU\tWindow\tIRTT";
+    let table = |routes: &[String]| format!("{header}\n{}", routes.join("\n"));
+
+    let default = route("eth0", [0; 4], [10, 128, 0, 1], 100, [0; 4]);
+    let host_route = route("eth0", [10, 128, 0, 1], [0; 4], 100, [255; 4]);
+    let subnet = route("eth0", [10, 128, 0, 0], [0; 4], 100, [255, 255, 240, 0]);
+    let other_subnet = route("eth0", [172, 17, 0, 0], [0; 4], 100, [255, 255, 255, 0]);
+    let other_iface = route("docker0", [10, 128, 0, 0], [0; 4], 0, [255, 255, 255, 0]);
+
+    // the /32 route to the gateway and subnets that don't contain it are skipped
+    assert_eq!(
+        route_table_broadcast(&table(&[
+            default.clone(),
+            host_route.clone(),
+            other_subnet.clone(),
+            subnet,
+            other_iface
+        ])),
+        Some(Ipv4Addr::new(10, 128, 15, 255))
+    );
+
+    // only a host route is left, which has no broadcast address
+    assert_eq!(
+        route_table_broadcast(&table(&[default.clone(), host_route, other_subnet])),
+        None
+    );
+    assert_eq!(route_table_broadcast(&table(&[])), None);
+}
+
+#[test]
+fn test_wake_options_default_broadcast() {
+    let packet = create_magic_packet("01:23:45:67:89:AB").unwrap();
+    let options = WakeOptions::new(&packet).default_broadcast();
+    let expected = default_broadcast_addr()
+        .map_or_else(|_| "255.255.255.255:9".into(), |addr| addr.to_string());
+
+    assert_eq!(options.broadcast_address, expected);
+}
+
+#[test]
+fn test_mac_from_arp_missing() {
+    // TEST-NET-3 addresses are never on the local network
+    let ip = IpAddr::V4(Ipv4Addr::new(203, 0, 113, 254));
+    assert!(matches!(
+        Mac::from_arp(ip),
+        Err(WakeError::ArpLookup { ip: missing, .. }) if missing == ip
+    ));
+
+    let err = Mac::from_arp(IpAddr::V6(Ipv6Addr::LOCALHOST)).unwrap_err();
+    assert!(err.to_string().contains("neighbor discovery"));
+}
@@ -0,0 +1,63 @@
+// Copyright (C) 2025 DarkCeptor44
+//
+// This file is part of waker.
+//
+// waker is free software: you can redistribute it and/or modify
+// it under theterms of the GNU Lesser General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// waker is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Lesser General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public License
+// along with waker.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::env;
+use waker::{
+    dhcp::{parse_dhcp_leases, parse_leases},
+    Mac,
+};
+
+const LEASES: &str = "\
+1735689600 01:23:45:67:89:ab 192.168.1.10 nas 01:01:23:45:67:89:ab
+1735689700 aa:bb:cc:dd:ee:ff 192.168.1.11 * *
+not a lease
+1735689800 zz:bb:cc:dd:ee:ff 192.168.1.12 desktop *
+duid 00:01:00:01:2c:d4:5e:aa:01:23:45:67:89:ab
+1735689900 11:22:33:44:55:66 192.168.1.13
+";
+
+#[test]
+fn test_parse_leases() {
+    let leases = parse_leases(LEASES);
+
+    assert_eq!(
+        leases.machines,
+        vec![
+            ("nas".to_string(), Mac([0x01, 0x23, 0x45, 0x67, 0x89, 0xAB])),
+            (
+                "192.168.1.11".to_string(),
+                Mac([0xAA, 0xBB, 0xCC, 0xDD, 0xEE, 0xFF])
+            ),
+        ]
+    );
+    assert_eq!(leases.skipped, 3);
+}
+
+#[test]
+fn test_parse_dhcp_leases_file() {
+    let path = env::temp_dir().join(format!("waker-leases-{}", std::process::id()));
+    std::fs::write(&path, LEASES).expect("Failed to write lease file");
+
+    let leases = parse_dhcp_leases(&path);
+    std::fs::remove_file(&path).unwrap_or_default();
+
+    assert_eq!(
+        leases.expect("Failed to parse lease file"),
+        parse_leases(LEASES)
+    );
+    assert!(parse_dhcp_leases(env::temp_dir().join("waker-missing.leases")).is_err());
+}
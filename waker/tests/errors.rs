@@ -0,0 +1,147 @@
+// Copyright (C) 2025 DarkCeptor44
+//
+// This file is part of waker.
+//
+// waker is free software: you can redistribute it and/or modify
+// it under theterms of the GNU Lesser General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// waker is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Lesser General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public License
+// along with waker.  If not, see <https://www.gnu.org/licenses/>.
+
+#![cfg(feature = "serde")]
+
+use std::{net::SocketAddr, time::Duration};
+use waker::{HwAddr, Mac, MacAddressError, WakeError};
+
+fn json<T: serde::Serialize + std::fmt::Display>(error: &T, code: &str) -> String {
+    format!(r#"{{"code":"{code}","message":"{error}"}}"#)
+}
+
+#[test]
+fn test_mac_address_error_serialize() {
+    let errors = [
+        (
+            MacAddressError::InvalidByteInMac("G".into()),
+            "invalid_byte",
+        ),
+        (
+            MacAddressError::InvalidCharAt {
+                index: 4,
+                found: 'X',
+            },
+            "invalid_char",
+        ),
+        (
+            MacAddressError::InvalidMacAddress("nas".into()),
+            "invalid_mac",
+        ),
+        (
+            MacAddressError::InvalidMachineLine("nas".into()),
+            "invalid_machine_line",
+        ),
+        (MacAddressError::InvalidLength(5), "invalid_length"),
+        (MacAddressError::NotUnicast(Mac([0xFF; 6])), "not_unicast"),
+        (
+            MacAddressError::Eui64NotSupported(HwAddr::Eui64([0x01; 8])),
+            "eui64_not_supported",
+        ),
+    ];
+
+    for (error, code) in errors {
+        assert_eq!(error.code(), code);
+        assert_eq!(
+            serde_json::to_string(&error).expect("Failed to serialize error"),
+            json(&error, code)
+        );
+    }
+}
+
+#[test]
+fn test_wake_error_serialize() {
+    let addr: SocketAddr = "192.168.1.10:22".parse().unwrap();
+    let errors = [
+        (WakeError::InvalidAddress("nas".into()), "invalid_address"),
+        (
+            WakeError::LoopbackBind {
+                bind: "127.0.0.1:0".parse().unwrap(),
+                broadcast: "255.255.255.255:9".parse().unwrap(),
+            },
+            "loopback_bind",
+        ),
+        (WakeError::InvalidUrl("wol://".into()), "invalid_url"),
+        (
+            WakeError::InvalidPacket("too short".into()),
+            "invalid_packet",
+        ),
+        (WakeError::MismatchedRepetition(7), "mismatched_repetition"),
+        (
+            WakeError::ReceiveTimeout(Duration::from_secs(1)),
+            "receive_timeout",
+        ),
+        (
+            WakeError::Unreachable {
+                addr,
+                timeout: Duration::from_secs(5),
+            },
+            "unreachable",
+        ),
+        (
+            WakeError::NoDefaultBroadcast("no route".into()),
+            "no_default_broadcast",
+        ),
+        (
+            WakeError::ArpLookup {
+                ip: "192.168.1.10".parse().unwrap(),
+                reason: "not in the cache".into(),
+            },
+            "arp_lookup",
+        ),
+        (
+            WakeError::InvalidProxyReply("no acceptable methods".into()),
+            "invalid_proxy_reply",
+        ),
+        (
+            WakeError::RepeatTooLarge {
+                count: 1000,
+                max: 100,
+            },
+            "repeat_too_large",
+        ),
+        (
+            WakeError::InvalidRelayMessage("missing relay header".into()),
+            "invalid_relay_message",
+        ),
+        (
+            WakeError::RelayTargetNotAllowed("192.168.1.10:9".parse().unwrap()),
+            "relay_target_not_allowed",
+        ),
+        (
+            WakeError::UnrelayablePacket("trailers are not relayed".into()),
+            "unrelayable_packet",
+        ),
+    ];
+
+    for (error, code) in errors {
+        assert_eq!(error.code(), code);
+        assert_eq!(
+            serde_json::to_string(&error).expect("Failed to serialize error"),
+            json(&error, code)
+        );
+    }
+
+    assert_eq!(
+        serde_json::to_string(&WakeError::InvalidUrl("wol://".into())).unwrap(),
+        r#"{"code":"invalid_url","message":"Invalid URL wol://"}"#
+    );
+    assert_eq!(
+        serde_json::to_string(&MacAddressError::InvalidLength(5)).unwrap(),
+        r#"{"code":"invalid_length","message":"Invalid MAC address length: expected 6 bytes, got 5"}"#
+    );
+}
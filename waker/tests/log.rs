@@ -0,0 +1,168 @@
+// Copyright (C) 2025 DarkCeptor44
+//
+// This file is part of waker.
+//
+// waker is free software: you can redistribute it and/or modify
+// it under theterms of the GNU Lesser General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// waker is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Lesser General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public License
+// along with waker.  If not, see <https://www.gnu.org/licenses/>.
+
+#![cfg(feature = "log")]
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use std::{
+    net::UdpSocket,
+    sync::{Mutex, OnceLock},
+    thread::{self, ThreadId},
+};
+use waker::{create_magic_packet, wake_device, WakeOptions};
+
+/// Keeps the thread of every record since the tests run in parallel and share the logger
+struct TestLogger(Mutex<Vec<(ThreadId, Level, String)>>);
+
+impl Log for TestLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= Level::Debug
+    }
+
+    fn log(&self, record: &Record) {
+        self.0.lock().expect("Failed to lock records").push((
+            thread::current().id(),
+            record.level(),
+            record.args().to_string(),
+        ));
+    }
+
+    fn flush(&self) {}
+}
+
+fn logger() -> &'static TestLogger {
+    static LOGGER: OnceLock<TestLogger> = OnceLock::new();
+
+    LOGGER.get_or_init(|| TestLogger(Mutex::new(Vec::new())))
+}
+
+/// Installs the logger once and returns the records logged from the current thread
+fn records() -> Vec<(Level, String)> {
+    static INIT: OnceLock<()> = OnceLock::new();
+
+    INIT.get_or_init(|| {
+        log::set_logger(logger()).expect("Failed to set logger");
+        log::set_max_level(LevelFilter::Debug);
+    });
+
+    logger()
+        .0
+        .lock()
+        .expect("Failed to lock records")
+        .iter()
+        .filter(|(id, _, _)| *id == thread::current().id())
+        .map(|(_, level, message)| (*level, message.clone()))
+        .collect()
+}
+
+#[test]
+fn test_log_packet_emits_record_with_mac() {
+    assert!(records().is_empty());
+
+    let rec_socket = UdpSocket::bind("127.0.0.1:0").expect("Failed to bind receiving socket");
+    let rec_addr = rec_socket
+        .local_addr()
+        .expect("Failed to get local address");
+    let packet = create_magic_packet("01:23:45:67:89:AB").expect("Failed to create magic packet");
+
+    wake_device(
+        WakeOptions::new(&packet)
+            .broadcast_address(rec_addr.to_string())
+            .log_packet(false),
+    )
+    .expect("Failed to wake device");
+    assert!(records().is_empty());
+
+    wake_device(
+        WakeOptions::new(&packet)
+            .broadcast_address(rec_addr.to_string())
+            .log_packet(true),
+    )
+    .expect("Failed to wake device");
+
+    let records = records();
+    assert_eq!(records.len(), 1);
+    assert!(records[0].1.contains("01:23:45:67:89:ab"));
+    assert!(records[0].1.contains("102 bytes"));
+    assert!(records[0].1.contains(&rec_addr.to_string()));
+    assert!(records[0].1.starts_with("Sending"));
+}
+
+#[test]
+fn test_log_packet_uses_relay_address() {
+    assert!(records().is_empty());
+
+    let relay_socket = UdpSocket::bind("127.0.0.1:0").expect("Failed to bind relay socket");
+    let relay_addr = relay_socket
+        .local_addr()
+        .expect("Failed to get local address");
+    let packet = create_magic_packet("01:23:45:67:89:AB").expect("Failed to create magic packet");
+
+    wake_device(
+        WakeOptions::new(&packet)
+            .broadcast_address("192.168.1.255:9")
+            .relay(relay_addr.to_string())
+            .log_packet(true),
+    )
+    .expect("Failed to wake device");
+
+    let records = records();
+    assert_eq!(records.len(), 1);
+    assert!(records[0].1.contains(&relay_addr.to_string()));
+    assert!(!records[0].1.contains("192.168.1.255:9"));
+}
+
+#[test]
+fn test_log_packet_marks_dry_run() {
+    assert!(records().is_empty());
+
+    let packet = create_magic_packet("01:23:45:67:89:AB").expect("Failed to create magic packet");
+
+    wake_device(
+        WakeOptions::new(&packet)
+            .broadcast_address("127.0.0.1:9")
+            .dry_run(true)
+            .log_packet(true),
+    )
+    .expect("Failed to wake device");
+
+    let records = records();
+    assert_eq!(records.len(), 1);
+    assert!(records[0].1.starts_with("Dry run, not sending"));
+    assert!(records[0].1.contains("127.0.0.1:9"));
+}
+
+#[test]
+fn test_loopback_bind_logs_warning() {
+    assert!(records().is_empty());
+
+    let packet = create_magic_packet("01:23:45:67:89:AB").expect("Failed to create magic packet");
+
+    wake_device(
+        WakeOptions::new(&packet)
+            .broadcast_address("192.168.1.255:9")
+            .bind_address("127.0.0.1:0")
+            .dry_run(true),
+    )
+    .expect("Failed to wake device");
+
+    let records = records();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].0, Level::Warn);
+    assert!(records[0].1.contains("127.0.0.1"));
+    assert!(records[0].1.contains("192.168.1.255:9"));
+}
@@ -16,7 +16,7 @@
 // along with waker.  If not, see <https://www.gnu.org/licenses/>.
 
 use std::str::FromStr;
-use waker::Mac;
+use waker::{create_magic_packet, parse_machine_line, HwAddr, Mac, MacAddressError};
 
 const MAC_BYTES: [u8; 6] = [0x01, 0x23, 0x45, 0x67, 0x89, 0xAB];
 
@@ -26,6 +26,187 @@ fn test_parse_mac_from_str() {
     assert_eq!(mac.0, MAC_BYTES);
 }
 
+#[test]
+fn test_parse_mac_separators() {
+    let inputs = [
+        "01:23:45:67:89:AB",
+        "01-23-45-67-89-AB",
+        "01_23_45_67_89_AB",
+        "01.23.45.67.89.AB",
+        "01.23.45.67.89.ab",
+        "01:23-45.67_89:ab",
+        "01.23.45:67:89:AB",
+    ];
+
+    for input in inputs {
+        assert_eq!(Mac::from_str(input), Ok(Mac(MAC_BYTES)), "{input}");
+    }
+
+    assert_eq!(
+        Mac::from_str("01.23.45.67.89.AB").unwrap().to_string(),
+        Mac::from_str("01:23:45:67:89:AB").unwrap().to_string()
+    );
+    assert!(Mac::from_str("0123.4567.89AB").is_err());
+    assert_eq!(
+        Mac::from_str("01.23.45.67.89/AB"),
+        Err(MacAddressError::InvalidCharAt {
+            index: 14,
+            found: '/'
+        })
+    );
+}
+
+#[test]
+fn test_parse_mac_strict() {
+    assert_eq!(Mac::from_str("01_23_45_67_89_ab"), Ok(Mac(MAC_BYTES)));
+    assert_eq!(
+        Mac::from_str_strict("01_23_45_67_89_ab"),
+        Err(MacAddressError::InvalidCharAt {
+            index: 2,
+            found: '_'
+        })
+    );
+
+    for input in [
+        "01:23:45:67:89:ab",
+        "01-23-45-67-89-ab",
+        "01.23.45.67.89.ab",
+        " 01:23:45:67:89:ab: ",
+    ] {
+        assert_eq!(Mac::from_str_strict(input), Ok(Mac(MAC_BYTES)), "{input}");
+    }
+}
+
+#[test]
+fn test_parse_mac_with_extra_separator() {
+    assert_eq!(Mac::from_str("01:23:45:67:89:AB:"), Ok(Mac(MAC_BYTES)));
+    assert_eq!(Mac::from_str(" 01:23:45:67:89:AB: "), Ok(Mac(MAC_BYTES)));
+    assert_eq!(Mac::from_str("01-23-45-67-89-AB- "), Ok(Mac(MAC_BYTES)));
+    assert_eq!(Mac::from_str(":01:23:45:67:89:AB"), Ok(Mac(MAC_BYTES)));
+    assert_eq!(
+        Mac::from_str("01:23:45:67:89:AB::"),
+        Err(MacAddressError::InvalidLength(18))
+    );
+    assert_eq!(
+        Mac::from_str("01:23:45:67:89:"),
+        Err(MacAddressError::InvalidLength(14))
+    );
+}
+
+#[test]
+fn test_validate_mac() {
+    assert_eq!(Mac::validate("01:23:45:67:89:AB"), Ok(()));
+    assert_eq!(Mac::validate("01-23-45-67-89-ab"), Ok(()));
+    assert_eq!(
+        Mac::validate("01:23:45:67:89"),
+        Err(MacAddressError::InvalidLength(14))
+    );
+    assert_eq!(
+        Mac::validate("01:23:45:67:89:AG"),
+        Err(MacAddressError::InvalidCharAt {
+            index: 16,
+            found: 'G'
+        })
+    );
+}
+
+#[test]
+fn test_mac_invalid_char_index() {
+    assert_eq!(
+        Mac::from_str("01:2X:45:67:89:AB"),
+        Err(MacAddressError::InvalidCharAt {
+            index: 4,
+            found: 'X'
+        })
+    );
+    assert_eq!(
+        Mac::from_str("01:23/45:67:89:AB"),
+        Err(MacAddressError::InvalidCharAt {
+            index: 5,
+            found: '/'
+        })
+    );
+    assert_eq!(
+        Mac::from_str("  01:2X:45:67:89:AB"),
+        Err(MacAddressError::InvalidCharAt {
+            index: 6,
+            found: 'X'
+        })
+    );
+}
+
+#[test]
+fn test_mac_administration_bits() {
+    // Intel OUI, assigned by the manufacturer
+    let vendor = Mac([0x00, 0x1B, 0x21, 0x12, 0x34, 0x56]);
+    assert!(vendor.is_universally_administered());
+    assert!(!vendor.is_locally_administered());
+
+    let local = Mac([0x02, 0x42, 0xAC, 0x11, 0x00, 0x02]);
+    assert!(local.is_locally_administered());
+    assert!(!local.is_universally_administered());
+}
+
+#[test]
+fn test_mac_with_bits() {
+    let vendor = Mac([0x00, 0x1B, 0x21, 0x12, 0x34, 0x56]);
+
+    let local = vendor.with_local_bit(true);
+    assert!(local.is_locally_administered());
+    assert_eq!(local.0[0], 0x02);
+    assert_eq!(local.0[1..], vendor.0[1..]);
+    assert_eq!(local.with_local_bit(false), vendor);
+
+    let multicast = vendor.with_multicast_bit(true);
+    assert!(multicast.is_multicast());
+    assert_eq!(multicast.0[0], 0x01);
+    assert_eq!(multicast.0[1..], vendor.0[1..]);
+    assert_eq!(multicast.with_multicast_bit(false), vendor);
+
+    assert_eq!(
+        vendor.with_local_bit(true).with_multicast_bit(true).0[0],
+        0x03
+    );
+    assert_eq!(Mac([0xFF; 6]).with_multicast_bit(false).0[0], 0xFE);
+}
+
+#[test]
+fn test_mac_eq_str() {
+    let mac = Mac(MAC_BYTES);
+
+    assert!(mac.eq_str("01:23:45:67:89:AB"));
+    assert!(mac.eq_str("01:23:45:67:89:ab"));
+    assert!(mac.eq_str("01-23-45-67-89-Ab"));
+    assert!(mac.eq_str(" 01.23.45.67.89.ab "));
+    assert!(!mac.eq_str("01:23:45:67:89:AC"));
+    assert!(!mac.eq_str("01:23:45:67:89"));
+}
+
+#[test]
+fn test_mac_partial_eq_str() {
+    let mac = Mac(MAC_BYTES);
+
+    assert_eq!(mac, "01:23:45:67:89:ab");
+    assert_eq!(mac, *"01-23-45-67-89-AB");
+    assert_ne!(mac, "01:23:45:67:89:ac");
+    assert_ne!(mac, "not a mac");
+}
+
+#[test]
+fn test_find_mac_in_ip_link_output() {
+    let output = "2: eth0: <BROADCAST,MULTICAST,UP,LOWER_UP> mtu 1500 qdisc fq_codel state UP mode DEFAULT group default qlen 1000
+    link/ether 01:23:45:67:89:ab brd ff:ff:ff:ff:ff:ff
+3: wlan0: <NO-CARRIER,BROADCAST,MULTICAST,UP> mtu 1500 qdisc noqueue state DOWN mode DORMANT group default qlen 1000
+    link/ieee802.11 02:00:00:00:00:00:00:01 brd ff:ff:ff:ff:ff:ff";
+
+    assert_eq!(Mac::find_in(output), Some(Mac(MAC_BYTES)));
+    assert_eq!(
+        Mac::find_all_in(output),
+        [Mac(MAC_BYTES), Mac([0xFF; 6]), Mac([0xFF; 6])]
+    );
+    assert_eq!(Mac::find_in("no addresses here"), None);
+}
+
 #[test]
 fn test_mac_display_lower() {
     let mac = Mac(MAC_BYTES);
@@ -53,3 +234,117 @@ fn test_mac_serde_deserialize() {
     let mac: Mac = serde_json::from_str(s).expect("Failed to deserialize MAC address");
     assert_eq!(mac, Mac(MAC_BYTES));
 }
+
+#[test]
+fn test_parse_hw_addr() {
+    assert_eq!(
+        HwAddr::from_str("01:23:45:67:89:AB"),
+        Ok(HwAddr::Mac48(MAC_BYTES))
+    );
+    assert_eq!(
+        HwAddr::from_str("01-23-45-67-89-ab-cd-ef"),
+        Ok(HwAddr::Eui64([
+            0x01, 0x23, 0x45, 0x67, 0x89, 0xAB, 0xCD, 0xEF
+        ]))
+    );
+    assert_eq!(
+        HwAddr::from_str("01:23:45:67:89:AB:CD"),
+        Err(MacAddressError::InvalidMacAddress(
+            "01:23:45:67:89:AB:CD".into()
+        ))
+    );
+    assert_eq!(
+        HwAddr::from_str("01:23:45:67:89:AG"),
+        Err(MacAddressError::InvalidByteInMac("G".into()))
+    );
+}
+
+#[test]
+fn test_hw_addr_magic_packet() {
+    let mac = HwAddr::from(Mac(MAC_BYTES));
+    assert_eq!(mac.as_mac(), Some(Mac(MAC_BYTES)));
+    assert!(create_magic_packet(mac).is_ok());
+
+    let eui = HwAddr::Eui64([0x01, 0x23, 0x45, 0x67, 0x89, 0xAB, 0xCD, 0xEF]);
+    assert_eq!(eui.to_string(), "01:23:45:67:89:ab:cd:ef");
+    assert_eq!(eui.as_mac(), None);
+    assert_eq!(
+        create_magic_packet(eui),
+        Err(MacAddressError::Eui64NotSupported(eui))
+    );
+}
+
+#[test]
+fn test_mac_to_str_buf() {
+    let mut buf = [0u8; 17];
+
+    for (bytes, expected) in [
+        (MAC_BYTES, "01:23:45:67:89:ab"),
+        ([0x00; 6], "00:00:00:00:00:00"),
+        ([0xFF; 6], "ff:ff:ff:ff:ff:ff"),
+        ([0xA0, 0x0B, 0xC1, 0x1D, 0xE2, 0x2F], "a0:0b:c1:1d:e2:2f"),
+    ] {
+        let mac = Mac(bytes);
+
+        assert_eq!(mac.to_str_buf(&mut buf), expected);
+        assert_eq!(mac.to_string(), expected);
+        assert_eq!(format!("{mac:X}"), expected.to_uppercase());
+    }
+}
+
+#[test]
+fn test_parse_machine_line() {
+    assert_eq!(
+        parse_machine_line(" nas = 01:23:45:67:89:AB "),
+        Ok(("nas".to_string(), Mac(MAC_BYTES)))
+    );
+    assert_eq!(
+        parse_machine_line("nas,01-23-45-67-89-ab"),
+        Ok(("nas".to_string(), Mac(MAC_BYTES)))
+    );
+    assert_eq!(
+        parse_machine_line("nas 01:23:45:67:89:AB"),
+        Err(MacAddressError::InvalidMachineLine(
+            "nas 01:23:45:67:89:AB".into()
+        ))
+    );
+    assert_eq!(
+        parse_machine_line("nas=01:23:45:67:89"),
+        Err(MacAddressError::InvalidLength(14))
+    );
+}
+
+#[test]
+fn test_mac_oui() {
+    assert_eq!(Mac(MAC_BYTES).oui(), [0x01, 0x23, 0x45]);
+}
+
+#[test]
+fn test_mac_from_hexdump_bytes() {
+    let header = [
+        0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01, 0x23, 0x45, 0x67, 0x89, 0xAB, 0x08, 0x42,
+    ];
+
+    assert_eq!(
+        Mac::from_hexdump_bytes(&header),
+        Some((Mac([0xFF; 6]), Mac(MAC_BYTES)))
+    );
+    assert_eq!(Mac::from_hexdump_bytes(&header[..11]), None);
+}
+
+#[test]
+fn test_mac_from_str_lenient() {
+    let unicode_hyphens = "01\u{2010}23\u{2010}45\u{2010}67\u{2010}89\u{2010}AB";
+    assert!(Mac::from_str(unicode_hyphens).is_err());
+    assert_eq!(Mac::from_str_lenient(unicode_hyphens), Ok(Mac(MAC_BYTES)));
+
+    assert_eq!(
+        Mac::from_str_lenient("01\u{A789}23\u{FF1A}45:67\u{2013}89\u{FF0E}ab"),
+        Ok(Mac(MAC_BYTES))
+    );
+    assert_eq!(
+        Mac::from_str_lenient("01:23:45:67:89:AB"),
+        Ok(Mac(MAC_BYTES))
+    );
+    assert!(Mac::from_str_lenient("01\u{2010}23\u{2010}45").is_err());
+}
@@ -15,8 +15,18 @@
 // You should have received a copy of the GNU Lesser General Public License
 // along with waker.  If not, see <https://www.gnu.org/licenses/>.
 
-use std::{net::UdpSocket, time::Duration};
-use waker::{create_magic_packet, wake_device, Mac, WakeOptions};
+use std::{
+    net::{Ipv4Addr, Ipv6Addr, UdpSocket},
+    sync::Mutex,
+    time::Duration,
+};
+use waker::{
+    build_arp_request, build_ethernet_frame, create_magic_packet, create_magic_packet_array,
+    create_magic_packet_strict, create_pattern_packet, magic_packet_layout,
+    parse_secure_on_password, wake_device, wake_device_local_addr, wake_device_with_stats,
+    wake_stream, Mac, MacAddressError, MagicPacket, MagicPacketN, MagicPacketReceiver, WakeError,
+    WakeOptions, WakeTarget, MAX_REPEAT, SYNC_STREAM, WOL_ETHERTYPE,
+};
 
 const MAC_BYTES: [u8; 6] = [0x01, 0x23, 0x45, 0x67, 0x89, 0xAB];
 const EXPECTED_PACKET: [u8; 102] = [
@@ -39,6 +49,228 @@ fn test_create_magic_packet_from_bytes() {
     assert_eq!(packet.0, EXPECTED_PACKET);
 }
 
+#[test]
+fn test_create_magic_packet_from_mac_ref() {
+    let mac = Mac(MAC_BYTES);
+    let packet = create_magic_packet(&mac).unwrap();
+    assert_eq!(packet.0, EXPECTED_PACKET);
+    assert_eq!(create_magic_packet_array(&mac).unwrap(), EXPECTED_PACKET);
+}
+
+#[test]
+fn test_magic_packet_for_mac() {
+    assert_eq!(MagicPacket::for_mac(Mac(MAC_BYTES)).0, EXPECTED_PACKET);
+}
+
+#[test]
+fn test_create_magic_packet_array() {
+    let packet = create_magic_packet_array("01:23:45:67:89:AB").unwrap();
+    assert_eq!(packet, EXPECTED_PACKET);
+}
+
+#[test]
+fn test_create_magic_packet_strict() {
+    let unicast = Mac([0x00, 0x1B, 0x21, 0x12, 0x34, 0x56]);
+    assert!(create_magic_packet_strict(unicast).is_ok());
+
+    assert_eq!(
+        create_magic_packet_strict("ff:ff:ff:ff:ff:ff"),
+        Err(MacAddressError::NotUnicast(Mac([0xFF; 6])))
+    );
+    assert_eq!(
+        create_magic_packet_strict("01:00:5e:00:00:fb"),
+        Err(MacAddressError::NotUnicast(Mac([
+            0x01, 0x00, 0x5E, 0x00, 0x00, 0xFB
+        ])))
+    );
+    assert_eq!(
+        create_magic_packet_strict("01:23:45"),
+        Err(MacAddressError::InvalidLength(8))
+    );
+}
+
+#[test]
+fn test_magic_packet_has_sync_stream() {
+    let mut packet = create_magic_packet(MAC_BYTES).expect("Failed to create magic packet");
+    assert!(packet.has_sync_stream());
+    assert_eq!(packet.0[..6], SYNC_STREAM);
+
+    packet.0[3] = 0xFE;
+    assert!(!packet.has_sync_stream());
+}
+
+#[test]
+fn test_magic_packet_from_bytes() {
+    let packet = MagicPacket::from_bytes(&EXPECTED_PACKET).expect("Failed to parse magic packet");
+    assert_eq!(packet.0, EXPECTED_PACKET);
+
+    let with_password = MagicPacketN::with_password(Mac(MAC_BYTES), [0x11; 6]);
+    assert_eq!(
+        MagicPacket::from_bytes(&with_password.0),
+        Err(WakeError::InvalidPacket(
+            "expected 102 bytes, got 108".into()
+        ))
+    );
+
+    let mut no_sync = EXPECTED_PACKET;
+    no_sync[3] = 0x00;
+    assert_eq!(
+        MagicPacket::from_bytes(&no_sync),
+        Err(WakeError::InvalidPacket(
+            "missing synchronization stream".into()
+        ))
+    );
+
+    let mut corrupted = EXPECTED_PACKET;
+    corrupted[6 + 6 * 9] = 0x00;
+    assert_eq!(
+        MagicPacket::from_bytes(&corrupted),
+        Err(WakeError::MismatchedRepetition(10))
+    );
+}
+
+#[test]
+fn test_magic_packet_mac() {
+    let mac = Mac(MAC_BYTES);
+
+    assert_eq!(MagicPacket::for_mac(mac).mac(), Some(mac));
+    assert_eq!(
+        MagicPacket::from(MagicPacketN::with_password(mac, [0x11; 6])).mac(),
+        Some(mac)
+    );
+    assert_eq!(MagicPacket(EXPECTED_PACKET[..12].to_vec()).mac(), Some(mac));
+    assert_eq!(MagicPacket(EXPECTED_PACKET[..11].to_vec()).mac(), None);
+}
+
+#[test]
+fn test_magic_packet_hex() {
+    let packet = MagicPacket::for_mac(Mac(MAC_BYTES));
+
+    let lower = packet.to_string();
+    assert_eq!(lower.len(), 204);
+    assert!(lower.starts_with("ffffffffffff0123456789ab0123456789ab"));
+    assert!(lower.ends_with("0123456789ab"));
+    assert_eq!(format!("{packet:x}"), lower);
+
+    let upper = format!("{packet:X}");
+    assert_eq!(upper.len(), 204);
+    assert_eq!(upper, lower.to_uppercase());
+
+    // the Debug output is unchanged
+    assert!(format!("{packet:?}").starts_with("MagicPacket([255, 255"));
+}
+
+#[test]
+fn test_magic_packet_try_from_iter() {
+    let packet = MagicPacket::try_from_iter(EXPECTED_PACKET.iter().copied())
+        .expect("Failed to collect magic packet");
+    assert_eq!(packet.0, EXPECTED_PACKET);
+    assert_eq!(
+        MagicPacket::try_from(EXPECTED_PACKET.as_slice()),
+        Ok(packet)
+    );
+
+    let with_password = EXPECTED_PACKET.iter().copied().chain([0x00; 6]);
+    assert!(MagicPacket::try_from_iter(with_password).is_ok());
+
+    assert_eq!(
+        MagicPacket::try_from_iter(EXPECTED_PACKET.iter().copied().take(60)),
+        Err(WakeError::InvalidPacket(
+            "expected 102 or 108 bytes, got 60".into()
+        ))
+    );
+
+    let mut corrupted = EXPECTED_PACKET;
+    corrupted[50] = 0x00;
+    assert_eq!(
+        MagicPacket::try_from_iter(corrupted),
+        Err(WakeError::MismatchedRepetition(8))
+    );
+}
+
+#[test]
+fn test_magic_packet_boxed_slice() {
+    let packet = MagicPacket::for_mac(Mac(MAC_BYTES));
+    let boxed = packet.clone().into_boxed_slice();
+    assert_eq!(*boxed, EXPECTED_PACKET);
+    assert_eq!(MagicPacket::from_boxed_slice(boxed), Ok(packet));
+
+    assert_eq!(
+        MagicPacket::from_boxed_slice(Box::new([0xFF; 60])),
+        Err(WakeError::InvalidPacket(
+            "expected 102 or 108 bytes, got 60".into()
+        ))
+    );
+}
+
+#[test]
+fn test_magic_packet_well_formed() {
+    MagicPacket::for_mac(Mac(MAC_BYTES)).assert_well_formed();
+    MagicPacket::from(MagicPacketN::new(Mac(MAC_BYTES))).assert_well_formed();
+    MagicPacket::from(MagicPacketN::with_password(Mac(MAC_BYTES), [0x00; 6])).assert_well_formed();
+}
+
+#[test]
+#[should_panic(expected = "expected 102 or 108 bytes, got 101")]
+fn test_magic_packet_truncated_not_well_formed() {
+    let mut packet = MagicPacket::for_mac(Mac(MAC_BYTES));
+    packet.0.pop();
+    packet.assert_well_formed();
+}
+
+#[test]
+fn test_magic_packet_eq_ignoring_password() {
+    let mac = Mac(MAC_BYTES);
+    let first = MagicPacket::from(MagicPacketN::with_password(mac, [0x11; 6]));
+    let second = MagicPacket::from(MagicPacketN::with_password(mac, [0x22; 6]));
+
+    assert_ne!(first, second);
+    assert!(first.eq_ignoring_password(&second));
+
+    let other = MagicPacket::from(MagicPacketN::with_password(Mac([0x02; 6]), [0x11; 6]));
+    assert!(!first.eq_ignoring_password(&other));
+    assert!(!first.eq_ignoring_password(&MagicPacket(vec![0xFF; 6])));
+}
+
+#[test]
+fn test_magic_packet_n() {
+    let packet = MagicPacketN::new(Mac(MAC_BYTES));
+    assert_eq!(packet.0, EXPECTED_PACKET);
+
+    let dynamic = MagicPacket::from(packet);
+    assert_eq!(dynamic, create_magic_packet(MAC_BYTES).unwrap());
+    assert_eq!(MagicPacketN::<102>::try_from(&dynamic), Ok(packet));
+
+    let password = [0x11, 0x22, 0x33, 0x44, 0x55, 0x66];
+    let secure_on = MagicPacketN::with_password(Mac(MAC_BYTES), password);
+    assert_eq!(secure_on.0[..102], EXPECTED_PACKET);
+    assert_eq!(secure_on.0[102..], password);
+
+    let dynamic = MagicPacket::from(secure_on);
+    assert_eq!(MagicPacketN::<108>::try_from(&dynamic), Ok(secure_on));
+    assert_eq!(
+        MagicPacketN::<102>::try_from(&dynamic),
+        Err(WakeError::InvalidPacket(
+            "expected 102 bytes, got 108".into()
+        ))
+    );
+}
+
+#[test]
+fn test_magic_packet_diff() {
+    let packet = create_magic_packet(MAC_BYTES).unwrap();
+    assert_eq!(packet.diff(&EXPECTED_PACKET), None);
+
+    let mut corrupted = EXPECTED_PACKET;
+    corrupted[42] = 0x00;
+    assert_eq!(packet.diff(&corrupted), Some(vec![(42, 0x01, 0x00)]));
+
+    assert_eq!(
+        packet.diff(&EXPECTED_PACKET[..100]),
+        Some(vec![(100, 0x89, 0x00), (101, 0xAB, 0x00)])
+    );
+}
+
 #[test]
 #[should_panic(expected = "InvalidLength(5)")]
 fn test_create_magic_packet_panics_on_invalid_mac_length() {
@@ -54,13 +286,8 @@ fn test_create_magic_packet_panics_on_invalid_mac_str() {
 
 #[test]
 fn test_wake_device() {
-    let rec_socket = UdpSocket::bind("127.0.0.1:0").expect("Failed to bind receiving socket");
-    rec_socket
-        .set_read_timeout(Some(Duration::from_millis(100)))
-        .expect("Failed to set read timeout");
-    let rec_addr = rec_socket
-        .local_addr()
-        .expect("Failed to get local address");
+    let receiver = MagicPacketReceiver::bind("127.0.0.1:0").expect("Failed to bind receiver");
+    let rec_addr = receiver.local_addr();
 
     let mac = Mac(MAC_BYTES);
     let packet = create_magic_packet(mac).expect("Failed to create magic packet");
@@ -68,23 +295,16 @@ fn test_wake_device() {
     wake_device(WakeOptions::new(&packet).broadcast_address(rec_addr.to_string()))
         .expect("Failed to wake device");
 
-    let mut buffer = [0u8; 102];
-    rec_socket
-        .recv_from(&mut buffer)
-        .expect("Failed to receive magic packet");
-
-    assert_eq!(buffer, EXPECTED_PACKET);
+    assert_eq!(
+        receiver.recv().expect("Failed to receive magic packet").0,
+        EXPECTED_PACKET
+    );
 }
 
 #[test]
 fn test_wake_device_with_localhost_bind() {
-    let rec_socket = UdpSocket::bind("127.0.0.1:0").expect("Failed to bind receiving socket");
-    rec_socket
-        .set_read_timeout(Some(Duration::from_millis(100)))
-        .expect("Failed to set read timeout");
-    let rec_addr = rec_socket
-        .local_addr()
-        .expect("Failed to get local address");
+    let receiver = MagicPacketReceiver::bind("127.0.0.1:0").expect("Failed to bind receiver");
+    let rec_addr = receiver.local_addr();
 
     let mac = Mac(MAC_BYTES);
     let packet = create_magic_packet(mac).expect("Failed to create magic packet");
@@ -96,12 +316,341 @@ fn test_wake_device_with_localhost_bind() {
     )
     .expect("Failed to wake device");
 
-    let mut buffer = [0u8; 102];
-    rec_socket
-        .recv_from(&mut buffer)
+    assert_eq!(
+        receiver.recv().expect("Failed to receive magic packet").0,
+        EXPECTED_PACKET
+    );
+}
+
+#[test]
+fn test_wake_device_with_source_interface_bind() {
+    // 127.0.0.2 is a loopback alias on Linux but not on every platform
+    if UdpSocket::bind("127.0.0.2:0").is_err() {
+        return;
+    }
+
+    let receiver = MagicPacketReceiver::bind("127.0.0.1:0").expect("Failed to bind receiver");
+    let rec_addr = receiver.local_addr();
+
+    let packet = create_magic_packet(MAC_BYTES).expect("Failed to create magic packet");
+
+    wake_device(
+        WakeOptions::new(&packet)
+            .broadcast_address(rec_addr.to_string())
+            .bind_address("127.0.0.2"),
+    )
+    .expect("Failed to wake device");
+
+    let (packet, src_addr) = receiver
+        .recv_from()
         .expect("Failed to receive magic packet");
 
-    assert_eq!(buffer, EXPECTED_PACKET);
+    assert_eq!(packet.0, EXPECTED_PACKET);
+    assert_eq!(src_addr.ip().to_string(), "127.0.0.2");
+}
+
+#[test]
+fn test_wake_device_with_borrowed_packet() {
+    let receiver = MagicPacketReceiver::bind("127.0.0.1:0").expect("Failed to bind receiver");
+    let rec_addr = receiver.local_addr();
+
+    let bytes: &[u8] = &EXPECTED_PACKET;
+
+    wake_device(WakeOptions::new(bytes).broadcast_address(rec_addr.to_string()))
+        .expect("Failed to wake device");
+
+    assert_eq!(
+        receiver.recv().expect("Failed to receive magic packet").0,
+        EXPECTED_PACKET
+    );
+
+    let array = create_magic_packet_array(MAC_BYTES).expect("Failed to create magic packet");
+
+    wake_device(WakeOptions::new(&array).broadcast_address(rec_addr.to_string()))
+        .expect("Failed to wake device");
+
+    assert_eq!(
+        receiver.recv().expect("Failed to receive magic packet").0,
+        EXPECTED_PACKET
+    );
+}
+
+#[test]
+fn test_wake_device_also_unicast() {
+    let receiver = MagicPacketReceiver::bind("127.0.0.1:0").expect("Failed to bind receiver");
+    let rec_addr = receiver.local_addr();
+
+    let packet = create_magic_packet(MAC_BYTES).expect("Failed to create magic packet");
+
+    wake_device(
+        WakeOptions::new(&packet)
+            .broadcast_address(rec_addr.to_string())
+            .also_unicast(rec_addr.ip()),
+    )
+    .expect("Failed to wake device");
+
+    for _ in 0..2 {
+        assert_eq!(
+            receiver.recv().expect("Failed to receive magic packet").0,
+            EXPECTED_PACKET
+        );
+    }
+}
+
+#[test]
+fn test_wake_device_repeat_jitter() {
+    let mut receiver = MagicPacketReceiver::bind("127.0.0.1:0").expect("Failed to bind receiver");
+    let rec_addr = receiver.local_addr();
+
+    let packet = create_magic_packet(MAC_BYTES).expect("Failed to create magic packet");
+
+    wake_device(
+        WakeOptions::new(&packet)
+            .broadcast_address(rec_addr.to_string())
+            .repeat(3)
+            .repeat_jitter(Duration::from_millis(1), Duration::from_millis(5)),
+    )
+    .expect("Failed to wake device");
+
+    for _ in 0..3 {
+        assert_eq!(
+            receiver.recv_mac().expect("Failed to receive magic packet"),
+            Mac(MAC_BYTES)
+        );
+    }
+
+    receiver
+        .set_timeout(Duration::from_millis(100))
+        .expect("Failed to set timeout");
+    let err = receiver.recv().expect_err("Received an extra magic packet");
+    assert_eq!(
+        err.downcast_ref::<WakeError>(),
+        Some(&WakeError::ReceiveTimeout(Duration::from_millis(100)))
+    );
+}
+
+#[test]
+fn test_receiver_rejects_invalid_packet() {
+    let receiver = MagicPacketReceiver::bind("127.0.0.1:0").expect("Failed to bind receiver");
+    let mut packet = EXPECTED_PACKET;
+    packet[50] = 0x00;
+
+    wake_device(WakeOptions::new(&packet).broadcast_address(receiver.local_addr().to_string()))
+        .expect("Failed to wake device");
+
+    let err = receiver
+        .recv_mac()
+        .expect_err("Received a valid magic packet");
+    assert_eq!(
+        err.downcast_ref::<WakeError>(),
+        Some(&WakeError::MismatchedRepetition(8))
+    );
+}
+
+#[test]
+fn test_wake_stream() {
+    let receiver = MagicPacketReceiver::bind("127.0.0.1:0").expect("Failed to bind receiver");
+    let rec_addr = receiver.local_addr();
+
+    let macs: Vec<Mac> = (0..5)
+        .map(|i| Mac([0x00, 0x1B, 0x21, 0x12, 0x34, i]))
+        .collect();
+    let options = WakeOptions::new(Vec::new()).broadcast_address(rec_addr.to_string());
+    let results: Vec<_> = wake_stream(macs.clone(), &options)
+        .expect("Failed to set up socket")
+        .collect();
+
+    assert_eq!(results.len(), macs.len());
+    for ((mac, result), expected) in results.iter().zip(&macs) {
+        assert_eq!(mac, expected);
+        assert!(result.is_ok());
+
+        assert_eq!(
+            receiver.recv_mac().expect("Failed to receive magic packet"),
+            *mac
+        );
+    }
+}
+
+#[test]
+fn test_wake_stream_dry_run_sends_nothing() {
+    let mut receiver = MagicPacketReceiver::bind("127.0.0.1:0").expect("Failed to bind receiver");
+    receiver
+        .set_timeout(Duration::from_millis(100))
+        .expect("Failed to set timeout");
+    let sent = Mutex::new(0);
+
+    let options = WakeOptions::new(Vec::new())
+        .broadcast_address(receiver.local_addr().to_string())
+        .bind_address("127.0.0.1:0")
+        .dry_run(true)
+        .on_sent(|_, _| *sent.lock().unwrap() += 1);
+    let results: Vec<_> = wake_stream([Mac(MAC_BYTES); 3], &options)
+        .expect("Failed to set up socket")
+        .collect();
+
+    assert!(results.iter().all(|(_, result)| result.is_ok()));
+    assert_eq!(*sent.lock().unwrap(), 0);
+    assert!(receiver.recv().is_err());
+}
+
+#[test]
+fn test_wake_stream_applies_options() {
+    let receiver = MagicPacketReceiver::bind("127.0.0.1:0").expect("Failed to bind receiver");
+    let sent = Mutex::new(Vec::new());
+
+    let options = WakeOptions::new(Vec::new())
+        .broadcast_address(receiver.local_addr().to_string())
+        .bind_address("127.0.0.1:0")
+        .repeat(2)
+        .secure_on([0x11; 6])
+        .trailer([0xAA; 2].as_slice())
+        .on_sent(|mac, _| sent.lock().unwrap().push(*mac));
+    let results: Vec<_> = wake_stream([Mac(MAC_BYTES)], &options)
+        .expect("Failed to set up socket")
+        .collect();
+    assert!(results[0].1.is_ok());
+
+    for _ in 0..2 {
+        let packet = receiver.recv().expect("Failed to receive magic packet");
+        assert_eq!(packet.0.len(), 110);
+        assert_eq!(packet.0[..102], EXPECTED_PACKET);
+        assert_eq!(
+            packet.0[102..],
+            [0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0xAA, 0xAA]
+        );
+    }
+    assert_eq!(*sent.lock().unwrap(), [Mac(MAC_BYTES), Mac(MAC_BYTES)]);
+
+    let too_many = WakeOptions::new(Vec::new()).repeat(MAX_REPEAT + 1);
+    assert!(wake_stream([Mac(MAC_BYTES)], &too_many).is_err());
+}
+
+#[test]
+fn test_wake_options_from_url() {
+    let packet = create_magic_packet(MAC_BYTES).expect("Failed to create magic packet");
+    let options = WakeOptions::from_url(
+        "wol://192.168.1.255:9?repeat=3&bind=0.0.0.0:0&unicast=192.168.1.10&relay=203.0.113.10:9009&strict=true",
+        &packet,
+    )
+    .expect("Failed to parse URL");
+
+    assert_eq!(options.packet.as_ref(), packet.0.as_slice());
+    assert_eq!(options.broadcast_address, "192.168.1.255:9");
+    assert_eq!(options.bind_address, "0.0.0.0:0");
+    assert_eq!(options.unicast, Some("192.168.1.10".parse().unwrap()));
+    assert_eq!(options.relay.as_deref(), Some("203.0.113.10:9009"));
+    assert_eq!(options.repeat, 3);
+    assert!(options.strict);
+}
+
+#[test]
+fn test_wake_options_from_url_invalid() {
+    let packet = create_magic_packet(MAC_BYTES).expect("Failed to create magic packet");
+
+    assert_eq!(
+        WakeOptions::from_url("http://192.168.1.255:9", &packet).err(),
+        Some(WakeError::InvalidUrl(
+            "http://192.168.1.255:9: expected the `wol://` scheme".into()
+        ))
+    );
+    assert_eq!(
+        WakeOptions::from_url("wol://192.168.1.255:9?retries=3", &packet).err(),
+        Some(WakeError::InvalidUrl(
+            "wol://192.168.1.255:9?retries=3: unknown parameter `retries`".into()
+        ))
+    );
+    assert_eq!(
+        WakeOptions::from_url("wol://192.168.1.255:9?strict=maybe", &packet).err(),
+        Some(WakeError::InvalidUrl(
+            "wol://192.168.1.255:9?strict=maybe: invalid value for strict `maybe`".into()
+        ))
+    );
+    assert!(matches!(
+        WakeOptions::from_url("wol://not an address", &packet),
+        Err(WakeError::InvalidAddress(_))
+    ));
+}
+
+#[test]
+fn test_wake_options_from_wake_target() {
+    let target = WakeTarget::new(Mac(MAC_BYTES)).with_name("nas");
+    let options = target.wake_options().broadcast_address("192.168.0.255:9");
+
+    assert_eq!(target.name.as_deref(), Some("nas"));
+    assert_eq!(&*options.packet, &EXPECTED_PACKET[..]);
+    assert_eq!(options.broadcast_address, "192.168.0.255:9");
+    assert_eq!(options.bind_address, "0.0.0.0:0");
+}
+
+#[test]
+fn test_resolved_addresses() {
+    let packet = create_magic_packet(MAC_BYTES).expect("Failed to create magic packet");
+
+    let options = WakeOptions::new(&packet);
+    assert_eq!(
+        options.resolved_broadcast(),
+        Ok("255.255.255.255:9".parse().unwrap())
+    );
+    assert_eq!(options.resolved_bind(), Ok("0.0.0.0:0".parse().unwrap()));
+
+    let options = WakeOptions::new(&packet)
+        .broadcast_address("192.168.0.255:7")
+        .bind_address("192.168.0.10");
+    assert_eq!(
+        options.resolved_broadcast(),
+        Ok("192.168.0.255:7".parse().unwrap())
+    );
+    assert_eq!(
+        options.resolved_bind(),
+        Ok("192.168.0.10:0".parse().unwrap())
+    );
+
+    let options = WakeOptions::new(&packet).broadcast_address("192.168.0.255");
+    assert!(matches!(
+        options.resolved_broadcast(),
+        Err(WakeError::InvalidAddress(_))
+    ));
+}
+
+#[test]
+fn test_check_bind_loopback_with_broadcast_target() {
+    let packet = create_magic_packet(MAC_BYTES).expect("Failed to create magic packet");
+    let options = WakeOptions::new(&packet)
+        .bind_address("127.0.0.1:0")
+        .broadcast_address("255.255.255.255:9");
+
+    assert_eq!(
+        options.check_bind(),
+        Err(WakeError::LoopbackBind {
+            bind: "127.0.0.1:0".parse().unwrap(),
+            broadcast: "255.255.255.255:9".parse().unwrap(),
+        })
+    );
+    assert!(WakeOptions::new(&packet).check_bind().is_ok());
+    assert!(WakeOptions::new(&packet)
+        .bind_address("127.0.0.1:0")
+        .broadcast_address("127.0.0.1:9")
+        .check_bind()
+        .is_ok());
+}
+
+#[test]
+fn test_wake_device_strict_rejects_loopback_bind() {
+    let packet = create_magic_packet(MAC_BYTES).expect("Failed to create magic packet");
+
+    let err = wake_device(
+        WakeOptions::new(&packet)
+            .bind_address("127.0.0.1:0")
+            .broadcast_address("255.255.255.255:9")
+            .strict(true),
+    )
+    .expect_err("Strict mode should reject a loopback bind");
+
+    assert!(matches!(
+        err.downcast_ref::<WakeError>(),
+        Some(WakeError::LoopbackBind { .. })
+    ));
 }
 
 #[test]
@@ -123,3 +672,465 @@ fn test_magic_packet_serde_deserialize() {
     let packet: MagicPacket = serde_json::from_str(&s).expect("Failed to deserialize magic packet");
     assert_eq!(packet.0, EXPECTED_PACKET);
 }
+
+#[test]
+fn test_wake_device_broadcast_v4() {
+    let receiver = MagicPacketReceiver::bind("127.0.0.1:0").expect("Failed to bind receiver");
+    let port = receiver.local_addr().port();
+    let packet = MagicPacket::for_mac(Mac(MAC_BYTES));
+
+    let typed = WakeOptions::new(&packet)
+        .broadcast_v4(Ipv4Addr::LOCALHOST, port)
+        .bind_address("127.0.0.1:0");
+    let parsed = WakeOptions::new(&packet).broadcast_address(format!("127.0.0.1:{port}"));
+    assert_eq!(typed.resolved_broadcast(), parsed.resolved_broadcast());
+
+    wake_device(typed).expect("Failed to send magic packet");
+    assert_eq!(
+        receiver.recv_mac().expect("Failed to receive magic packet"),
+        Mac(MAC_BYTES)
+    );
+}
+
+#[test]
+fn test_wake_device_picks_socket_family() {
+    let packet = MagicPacket::for_mac(Mac(MAC_BYTES));
+
+    for (receiver_addr, bind) in [
+        ("127.0.0.1:0", "0.0.0.0:0"),
+        ("127.0.0.1:0", "[::]:0"),
+        ("[::1]:0", "0.0.0.0:0"),
+        ("[::1]:0", "[::]:0"),
+    ] {
+        let receiver = MagicPacketReceiver::bind(receiver_addr).expect("Failed to bind receiver");
+        let target = receiver.local_addr();
+
+        let local = wake_device_local_addr(
+            WakeOptions::new(&packet)
+                .broadcast_address(target.to_string())
+                .bind_address(bind),
+        )
+        .expect("Failed to send magic packet");
+
+        assert_eq!(local.is_ipv4(), target.is_ipv4(), "{target} from {bind}");
+        assert_eq!(
+            receiver.recv_mac().expect("Failed to receive magic packet"),
+            Mac(MAC_BYTES)
+        );
+    }
+
+    // a specific bind address is never swapped
+    let receiver = MagicPacketReceiver::bind("[::1]:0").expect("Failed to bind receiver");
+    assert!(wake_device(
+        WakeOptions::new(&packet)
+            .broadcast_address(receiver.local_addr().to_string())
+            .bind_address("127.0.0.1:0"),
+    )
+    .is_err());
+}
+
+#[test]
+fn test_wake_device_broadcast_v6() {
+    let receiver = MagicPacketReceiver::bind("[::1]:0").expect("Failed to bind receiver");
+    let port = receiver.local_addr().port();
+    let packet = MagicPacket::for_mac(Mac(MAC_BYTES));
+
+    let typed = WakeOptions::new(&packet)
+        .broadcast_v6(Ipv6Addr::LOCALHOST, port)
+        .bind_address("[::1]:0");
+    let parsed = WakeOptions::new(&packet).broadcast_address(format!("[::1]:{port}"));
+    assert_eq!(typed.resolved_broadcast(), parsed.resolved_broadcast());
+
+    wake_device(typed).expect("Failed to send magic packet");
+    assert_eq!(
+        receiver.recv_mac().expect("Failed to receive magic packet"),
+        Mac(MAC_BYTES)
+    );
+}
+
+#[test]
+fn test_wake_device_local_addr() {
+    let receiver = MagicPacketReceiver::bind("127.0.0.1:0").expect("Failed to bind receiver");
+    let packet = MagicPacket::for_mac(Mac(MAC_BYTES));
+    let options = WakeOptions::new(&packet)
+        .broadcast_address(receiver.local_addr().to_string())
+        .bind_address("127.0.0.1:0");
+
+    let local_addr = wake_device_local_addr(options).expect("Failed to send magic packet");
+    let (_, from) = receiver
+        .recv_from()
+        .expect("Failed to receive magic packet");
+
+    assert_ne!(local_addr.port(), 0);
+    assert_eq!(from, local_addr);
+}
+
+#[test]
+fn test_wake_device_dry_run() {
+    let mut receiver = MagicPacketReceiver::bind("127.0.0.1:0").expect("Failed to bind receiver");
+    let packet = MagicPacket::for_mac(Mac(MAC_BYTES));
+    let options = WakeOptions::new(&packet)
+        .broadcast_address(receiver.local_addr().to_string())
+        .bind_address("127.0.0.1:0")
+        .dry_run(true);
+
+    wake_device(options.clone().repeat(3)).expect("Failed to dry run");
+
+    receiver
+        .set_timeout(Duration::from_millis(100))
+        .expect("Failed to set timeout");
+    let err = receiver
+        .recv()
+        .expect_err("Received a magic packet in a dry run");
+    assert_eq!(
+        err.downcast_ref::<WakeError>(),
+        Some(&WakeError::ReceiveTimeout(Duration::from_millis(100)))
+    );
+
+    let err = wake_device(options.clone().broadcast_address("not an address"))
+        .expect_err("Dry run skipped address validation");
+    assert!(matches!(
+        err.downcast_ref::<WakeError>(),
+        Some(WakeError::InvalidAddress(_))
+    ));
+
+    let err = wake_device(options.broadcast_address("192.168.1.255:9").strict(true))
+        .expect_err("Dry run skipped strict checks");
+    assert!(matches!(
+        err.downcast_ref::<WakeError>(),
+        Some(WakeError::LoopbackBind { .. })
+    ));
+}
+
+#[test]
+fn test_magic_packet_layout() {
+    let layout = magic_packet_layout();
+    let offsets: Vec<usize> = layout.mac_slots.iter().map(|slot| slot.start).collect();
+
+    assert_eq!(layout.sync, 0..6);
+    assert_eq!(offsets, (6..102).step_by(6).collect::<Vec<_>>());
+    assert_eq!(layout.mac_slots[0], 6..12);
+    assert_eq!(layout.mac_slots[15], 96..102);
+    assert_eq!(layout.password, 102..108);
+
+    for slot in layout.mac_slots {
+        assert_eq!(EXPECTED_PACKET[slot], MAC_BYTES);
+    }
+}
+
+#[test]
+fn test_wake_device_trailer() {
+    let receiver = UdpSocket::bind("127.0.0.1:0").expect("Failed to bind receiving socket");
+    let packet = MagicPacket::from(MagicPacketN::with_password(Mac(MAC_BYTES), [0x11; 6]));
+    let trailer = [0xDE, 0xAD, 0xBE, 0xEF];
+
+    wake_device(
+        WakeOptions::new(&packet)
+            .broadcast_address(receiver.local_addr().unwrap().to_string())
+            .bind_address("127.0.0.1:0")
+            .trailer(trailer.as_slice()),
+    )
+    .expect("Failed to wake device");
+
+    let mut buffer = [0u8; 1024];
+    let (len, _) = receiver
+        .recv_from(&mut buffer)
+        .expect("Failed to receive magic packet");
+
+    assert_eq!(len, 112);
+    assert_eq!(buffer[..108], packet.0);
+    assert_eq!(buffer[108..len], trailer);
+}
+
+#[test]
+fn test_wake_device_secure_on() {
+    let receiver = MagicPacketReceiver::bind("127.0.0.1:0").expect("Failed to bind receiver");
+    let rec_addr = receiver.local_addr();
+    let packet = create_magic_packet(MAC_BYTES).expect("Failed to create magic packet");
+    let password = parse_secure_on_password("11:22:33:44:55:66").expect("Failed to parse password");
+    let send = |options: WakeOptions| {
+        wake_device(
+            options
+                .broadcast_address(rec_addr.to_string())
+                .bind_address("127.0.0.1:0"),
+        )
+        .expect("Failed to wake device");
+
+        receiver.recv().expect("Failed to receive magic packet").0
+    };
+
+    let received = send(WakeOptions::new(&packet).secure_on(password));
+    assert_eq!(received.len(), 108);
+    assert_eq!(received[..102], EXPECTED_PACKET);
+    assert_eq!(received[102..], [0x11, 0x22, 0x33, 0x44, 0x55, 0x66]);
+
+    // the password goes before the trailer
+    let received = send(
+        WakeOptions::new(&packet)
+            .secure_on(password)
+            .trailer([0xAA; 2].as_slice()),
+    );
+    assert_eq!(
+        received[102..],
+        [0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0xAA, 0xAA]
+    );
+
+    // nothing changes without a password
+    assert_eq!(send(WakeOptions::new(&packet)), EXPECTED_PACKET);
+
+    assert_eq!(
+        parse_secure_on_password("11-22-33-44-55-66").unwrap(),
+        password
+    );
+    assert!(parse_secure_on_password("11:22:33").is_err());
+}
+
+#[test]
+fn test_wake_options_expected_packet_len() {
+    let packet = MagicPacket::for_mac(Mac(MAC_BYTES));
+    let secure_on = MagicPacketN::with_password(Mac(MAC_BYTES), [0x11; 6]);
+
+    assert_eq!(WakeOptions::new(&packet).expected_packet_len(), 102);
+    assert_eq!(WakeOptions::new(&secure_on).expected_packet_len(), 108);
+
+    let receiver = UdpSocket::bind("127.0.0.1:0").expect("Failed to bind receiving socket");
+    let options = WakeOptions::new(&secure_on)
+        .broadcast_address(receiver.local_addr().unwrap().to_string())
+        .bind_address("127.0.0.1:0")
+        .trailer([0xAA; 3].as_slice());
+    assert_eq!(options.expected_packet_len(), 111);
+
+    let mut buffer = vec![0u8; options.expected_packet_len() + 1];
+    wake_device(options).expect("Failed to wake device");
+    let (len, _) = receiver
+        .recv_from(&mut buffer)
+        .expect("Failed to receive magic packet");
+    assert_eq!(len, 111);
+}
+
+#[test]
+fn test_wake_device_pattern_packet() {
+    let receiver = UdpSocket::bind("127.0.0.1:0").expect("Failed to bind receiving socket");
+    let pattern = [0xDE, 0xAD, 0xBE, 0xEF, 0x00, 0x01];
+    let packet = create_pattern_packet(&pattern);
+    assert!(MagicPacket::try_from(packet.as_ref()).is_err());
+
+    wake_device(
+        WakeOptions::new(&packet)
+            .broadcast_address(receiver.local_addr().unwrap().to_string())
+            .bind_address("127.0.0.1:0"),
+    )
+    .expect("Failed to wake device");
+
+    let mut buffer = [0u8; 1024];
+    let (len, _) = receiver
+        .recv_from(&mut buffer)
+        .expect("Failed to receive pattern packet");
+    assert_eq!(&buffer[..len], pattern);
+}
+
+#[test]
+fn test_wake_device_repeat_interval() {
+    let mut receiver = MagicPacketReceiver::bind("127.0.0.1:0").expect("Failed to bind receiver");
+    receiver
+        .set_timeout(Duration::from_millis(200))
+        .expect("Failed to set timeout");
+    let rec_addr = receiver.local_addr();
+    let packet = create_magic_packet(MAC_BYTES).expect("Failed to create magic packet");
+    let send = |count| {
+        let start = std::time::Instant::now();
+        wake_device(
+            WakeOptions::new(&packet)
+                .broadcast_address(rec_addr.to_string())
+                .bind_address("127.0.0.1:0")
+                .repeat(count)
+                .interval(Duration::from_millis(20)),
+        )
+        .expect("Failed to wake device");
+        let elapsed = start.elapsed();
+
+        let mut received = 0;
+        while receiver.recv().is_ok() {
+            received += 1;
+        }
+        (received, elapsed)
+    };
+
+    let (received, elapsed) = send(3);
+    assert_eq!(received, 3);
+    assert!(elapsed >= Duration::from_millis(40));
+
+    // zero is treated as one
+    let (received, _) = send(0);
+    assert_eq!(received, 1);
+}
+
+#[test]
+fn test_wake_device_with_stats() {
+    let receiver = UdpSocket::bind("127.0.0.1:0").expect("Failed to bind receiving socket");
+    let packet = create_magic_packet(MAC_BYTES).expect("Failed to create magic packet");
+
+    let options = WakeOptions::new(&packet)
+        .broadcast_address(receiver.local_addr().unwrap().to_string())
+        .bind_address("127.0.0.1:0")
+        .repeat(2)
+        .interval(Duration::from_millis(20));
+
+    let stats = wake_device_with_stats(options).expect("Failed to wake device");
+
+    assert!(stats.send_time > Duration::ZERO);
+    assert!(stats.bind_time <= stats.total);
+    assert!(stats.send_time <= stats.total);
+    assert!(stats.bind_time + stats.send_time <= stats.total);
+
+    // the pause between the repeats only counts towards the total
+    assert!(stats.total >= Duration::from_millis(20));
+}
+
+#[test]
+fn test_build_ethernet_frame() {
+    let src = Mac([0x00, 0x1B, 0x21, 0x12, 0x34, 0x56]);
+    let packet = MagicPacket::for_mac(Mac(MAC_BYTES));
+    let frame = build_ethernet_frame(src, Mac([0xFF; 6]), &packet);
+
+    assert_eq!(frame.len(), 14 + 102);
+    assert_eq!(Mac::from_hexdump_bytes(&frame), Some((Mac([0xFF; 6]), src)));
+    assert_eq!(frame[12..14], WOL_ETHERTYPE.to_be_bytes());
+    assert_eq!(frame[14..], EXPECTED_PACKET);
+
+    // short payloads are padded to the minimum frame length
+    let frame = build_ethernet_frame(src, Mac([0xFF; 6]), &MagicPacket(vec![0xAA; 4]));
+    assert_eq!(frame.len(), 60);
+    assert_eq!(frame[14..18], [0xAA; 4]);
+    assert!(frame[18..].iter().all(|&b| b == 0));
+}
+
+#[test]
+fn test_build_arp_request() {
+    let src = Mac([0x00, 0x1B, 0x21, 0x12, 0x34, 0x56]);
+    let frame = build_arp_request(
+        src,
+        Ipv4Addr::new(192, 168, 1, 2),
+        Mac(MAC_BYTES),
+        Ipv4Addr::new(192, 168, 1, 10),
+    );
+
+    assert_eq!(frame.len(), 60);
+    assert_eq!(Mac::from_hexdump_bytes(&frame), Some((Mac([0xFF; 6]), src)));
+    assert_eq!(frame[12..14], [0x08, 0x06]);
+
+    let arp = &frame[14..42];
+    assert_eq!(arp[..8], [0x00, 0x01, 0x08, 0x00, 6, 4, 0x00, 0x01]);
+    assert_eq!(arp[8..14], src.0);
+    assert_eq!(arp[14..18], [192, 168, 1, 2]);
+    assert_eq!(arp[18..24], MAC_BYTES);
+    assert_eq!(arp[24..28], [192, 168, 1, 10]);
+}
+
+#[test]
+fn test_wake_device_repeat_too_large() {
+    let packet = create_magic_packet(MAC_BYTES).expect("Failed to create magic packet");
+    let err = wake_device(
+        WakeOptions::new(&packet)
+            .bind_address("127.0.0.1:0")
+            .repeat(MAX_REPEAT + 1),
+    )
+    .unwrap_err();
+
+    assert_eq!(
+        err.downcast_ref::<WakeError>(),
+        Some(&WakeError::RepeatTooLarge {
+            count: MAX_REPEAT + 1,
+            max: MAX_REPEAT
+        })
+    );
+
+    // the cap can be raised, a dry run keeps the test from sending anything
+    wake_device(
+        WakeOptions::new(&packet)
+            .repeat(MAX_REPEAT + 1)
+            .max_repeat(MAX_REPEAT + 1)
+            .dry_run(true),
+    )
+    .expect("Failed to wake device");
+}
+
+#[test]
+fn test_magic_packet_verify_repetitions() {
+    let mut packet = MagicPacket::for_mac(Mac(MAC_BYTES));
+    assert_eq!(packet.verify_repetitions(), Ok(Mac(MAC_BYTES)));
+
+    // the 7th repetition starts after the sync stream and 6 other repetitions
+    packet.0[6 + 6 * 6 + 5] ^= 0xFF;
+    assert_eq!(
+        packet.verify_repetitions(),
+        Err(WakeError::MismatchedRepetition(7))
+    );
+
+    let truncated = MagicPacket(EXPECTED_PACKET[..60].to_vec());
+    assert_eq!(
+        truncated.verify_repetitions(),
+        Err(WakeError::MismatchedRepetition(10))
+    );
+}
+
+#[test]
+fn test_wake_options_validate() {
+    let packet = create_magic_packet(MAC_BYTES).expect("Failed to create magic packet");
+    assert_eq!(WakeOptions::new(&packet).validate(), Ok(()));
+
+    let errors = WakeOptions::new(&packet)
+        .broadcast_address("256.0.0.1:9")
+        .bind_address("not-an-address")
+        .validate()
+        .unwrap_err();
+
+    assert_eq!(errors.len(), 2);
+    assert!(errors
+        .iter()
+        .all(|e| matches!(e, WakeError::InvalidAddress(_))));
+    assert!(errors[0].to_string().contains("256.0.0.1:9"));
+    assert!(errors[1].to_string().contains("not-an-address"));
+
+    let errors = WakeOptions::new(&packet)
+        .bind_address("127.0.0.1:0")
+        .strict(true)
+        .repeat(MAX_REPEAT + 1)
+        .validate()
+        .unwrap_err();
+    assert_eq!(errors.len(), 2);
+    assert_eq!(errors[0].code(), "repeat_too_large");
+    assert_eq!(errors[1].code(), "loopback_bind");
+}
+
+#[test]
+fn test_wake_options_on_sent() {
+    let receiver = UdpSocket::bind("127.0.0.1:0").expect("Failed to bind receiving socket");
+    let rec_addr = receiver.local_addr().unwrap();
+    let packet = create_magic_packet(MAC_BYTES).expect("Failed to create magic packet");
+    let sent = Mutex::new(Vec::new());
+
+    wake_device(
+        WakeOptions::new(&packet)
+            .broadcast_address(rec_addr.to_string())
+            .bind_address("127.0.0.1:0")
+            .repeat(2)
+            .on_sent(|mac, addr| sent.lock().unwrap().push((*mac, *addr))),
+    )
+    .expect("Failed to wake device");
+
+    assert_eq!(
+        sent.into_inner().unwrap(),
+        [(Mac(MAC_BYTES), rec_addr), (Mac(MAC_BYTES), rec_addr)]
+    );
+
+    // nothing is sent in a dry run so the callback isn't called
+    let called = Mutex::new(false);
+    wake_device(
+        WakeOptions::new(&packet)
+            .dry_run(true)
+            .on_sent(|_, _| *called.lock().unwrap() = true),
+    )
+    .expect("Failed to wake device");
+    assert!(!called.into_inner().unwrap());
+}
@@ -0,0 +1,45 @@
+// Copyright (C) 2025 DarkCeptor44
+//
+// This file is part of waker.
+//
+// waker is free software: you can redistribute it and/or modify
+// it under theterms of the GNU Lesser General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// waker is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Lesser General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public License
+// along with waker.  If not, see <https://www.gnu.org/licenses/>.
+
+#![cfg(feature = "oui-db")]
+
+use waker::Mac;
+
+#[test]
+fn test_vendor_known() {
+    assert_eq!(
+        Mac([0x3C, 0x07, 0x54, 0x12, 0x34, 0x56]).vendor(),
+        Some("Apple")
+    );
+    assert_eq!(
+        Mac([0x00, 0x1B, 0x21, 0x12, 0x34, 0x56]).vendor(),
+        Some("Intel")
+    );
+    assert_eq!(
+        Mac([0xB8, 0x27, 0xEB, 0x12, 0x34, 0x56]).vendor(),
+        Some("Raspberry Pi")
+    );
+}
+
+#[test]
+fn test_vendor_unknown() {
+    assert_eq!(Mac([0x00, 0x00, 0x01, 0x12, 0x34, 0x56]).vendor(), None);
+
+    // locally administered and multicast addresses have no vendor even if the rest of the OUI matches
+    assert_eq!(Mac([0x02, 0x1B, 0x21, 0x12, 0x34, 0x56]).vendor(), None);
+    assert_eq!(Mac([0x01, 0x1B, 0x21, 0x12, 0x34, 0x56]).vendor(), None);
+}
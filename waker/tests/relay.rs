@@ -0,0 +1,230 @@
+// Copyright (C) 2025 DarkCeptor44
+//
+// This file is part of waker.
+//
+// waker is free software: you can redistribute it and/or modify
+// it under theterms of the GNU Lesser General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// waker is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Lesser General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public License
+// along with waker.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::{
+    net::{SocketAddr, UdpSocket},
+    time::Duration,
+};
+use waker::{
+    create_magic_packet,
+    relay::{self, RelayMessage, RELAY_MAGIC},
+    wake_device, Mac, MagicPacket, MagicPacketN, MagicPacketReceiver, WakeError, WakeOptions,
+};
+
+const MAC_BYTES: [u8; 6] = [0x01, 0x23, 0x45, 0x67, 0x89, 0xAB];
+
+#[test]
+fn test_relay_encode_format() {
+    let packet = create_magic_packet(MAC_BYTES).expect("Failed to create magic packet");
+    let message = relay::encode(&packet.0, "192.168.1.255:9".parse().unwrap());
+
+    assert_eq!(message[..4], RELAY_MAGIC);
+    assert_eq!(message[4], 4);
+    assert_eq!(message[5..9], [192, 168, 1, 255]);
+    assert_eq!(message[9..11], [0, 9]);
+    assert_eq!(message[11..], packet.0);
+}
+
+#[test]
+fn test_relay_decode() {
+    let packet = create_magic_packet(MAC_BYTES).expect("Failed to create magic packet");
+
+    for target in ["192.168.1.255:9", "[ff02::1]:7"] {
+        let target: SocketAddr = target.parse().unwrap();
+        let message = relay::decode(&relay::encode(&packet.0, target))
+            .expect("Failed to decode relay message");
+
+        assert_eq!(message.target, target);
+        assert_eq!(message.packet, packet);
+    }
+}
+
+#[test]
+fn test_relay_decode_invalid() {
+    let packet = create_magic_packet(MAC_BYTES).expect("Failed to create magic packet");
+    let message = relay::encode(&packet.0, "192.168.1.255:9".parse().unwrap());
+
+    assert_eq!(
+        relay::decode(&packet.0),
+        Err(WakeError::InvalidRelayMessage(
+            "missing relay header".into()
+        ))
+    );
+    assert_eq!(
+        relay::decode(&message[..7]),
+        Err(WakeError::InvalidRelayMessage(
+            "truncated broadcast address".into()
+        ))
+    );
+    assert_eq!(
+        relay::decode(&message[..11]),
+        Err(WakeError::InvalidRelayMessage(
+            "missing magic packet".into()
+        ))
+    );
+
+    let mut unknown_version = message.clone();
+    unknown_version[4] = 5;
+    assert_eq!(
+        relay::decode(&unknown_version),
+        Err(WakeError::InvalidRelayMessage(
+            "unknown IP version 5".into()
+        ))
+    );
+}
+
+#[test]
+fn test_wake_device_through_relay() {
+    let relay_socket = UdpSocket::bind("127.0.0.1:0").expect("Failed to bind relay socket");
+    relay_socket
+        .set_read_timeout(Some(Duration::from_millis(100)))
+        .expect("Failed to set read timeout");
+    let relay_addr = relay_socket
+        .local_addr()
+        .expect("Failed to get local address");
+
+    let packet = create_magic_packet(MAC_BYTES).expect("Failed to create magic packet");
+
+    wake_device(
+        WakeOptions::new(&packet)
+            .broadcast_address("192.168.1.255:9")
+            .relay(relay_addr.to_string()),
+    )
+    .expect("Failed to send magic packet to relay");
+
+    let message = relay::receive(&relay_socket).expect("Failed to receive relay message");
+
+    assert_eq!(message.target, "192.168.1.255:9".parse().unwrap());
+    assert_eq!(message.packet, packet);
+}
+
+#[test]
+fn test_relay_decode_rejects_arbitrary_payloads() {
+    let packet = create_magic_packet(MAC_BYTES).expect("Failed to create magic packet");
+    let target = "192.168.1.255:9".parse().unwrap();
+
+    assert!(matches!(
+        relay::decode(&relay::encode(b"GET / HTTP/1.1\r\n\r\n", target)),
+        Err(WakeError::InvalidPacket(_))
+    ));
+
+    let mut corrupted = packet.0.clone();
+    corrupted[50] = 0x00;
+    assert_eq!(
+        relay::decode(&relay::encode(&corrupted, target)),
+        Err(WakeError::MismatchedRepetition(8))
+    );
+
+    // a SecureOn password can be 4 or 6 bytes but nothing else can follow the packet
+    for (extra, valid) in [(4, true), (5, false), (6, true), (100, false)] {
+        let with_extra = [packet.0.as_slice(), &vec![0x11; extra]].concat();
+        assert_eq!(
+            relay::decode(&relay::encode(&with_extra, target)).is_ok(),
+            valid,
+            "{extra} extra bytes"
+        );
+    }
+}
+
+#[test]
+fn test_relay_rebroadcast_only_to_allowed_targets() {
+    let receiver = MagicPacketReceiver::bind("127.0.0.1:0").expect("Failed to bind receiver");
+    let message = RelayMessage {
+        target: receiver.local_addr(),
+        packet: MagicPacket::for_mac(Mac(MAC_BYTES)),
+    };
+
+    let error = message
+        .rebroadcast()
+        .expect_err("Relayed to a unicast address");
+    assert_eq!(
+        error.downcast_ref::<WakeError>(),
+        Some(&WakeError::RelayTargetNotAllowed(receiver.local_addr()))
+    );
+
+    message
+        .rebroadcast_allowing(&[receiver.local_addr().ip()])
+        .expect("Failed to relay to an allowed address");
+    assert_eq!(
+        receiver.recv_mac().expect("Failed to receive magic packet"),
+        Mac(MAC_BYTES)
+    );
+}
+
+#[test]
+fn test_wake_device_through_relay_of_other_family() {
+    let packet = create_magic_packet(MAC_BYTES).expect("Failed to create magic packet");
+
+    // the default bind address is IPv4 and the target's family shouldn't matter either
+    for (relay_bind, target) in [
+        ("[::1]:0", "192.168.1.255:9"),
+        ("[::1]:0", "[ff02::1]:9"),
+        ("127.0.0.1:0", "[ff02::1]:9"),
+    ] {
+        let relay_socket = UdpSocket::bind(relay_bind).expect("Failed to bind relay socket");
+        relay_socket
+            .set_read_timeout(Some(Duration::from_millis(100)))
+            .expect("Failed to set read timeout");
+        let relay_addr = relay_socket
+            .local_addr()
+            .expect("Failed to get local address");
+
+        wake_device(
+            WakeOptions::new(&packet)
+                .broadcast_address(target)
+                .relay(relay_addr.to_string()),
+        )
+        .unwrap_or_else(|e| panic!("Failed to send magic packet to relay {relay_addr}: {e}"));
+
+        let message = relay::receive(&relay_socket).expect("Failed to receive relay message");
+
+        assert_eq!(message.target, target.parse().unwrap());
+        assert_eq!(message.packet, packet);
+    }
+}
+
+#[test]
+fn test_wake_device_through_relay_rejects_extra_bytes() {
+    let packet = create_magic_packet(MAC_BYTES).expect("Failed to create magic packet");
+    let with_password = MagicPacketN::with_password(Mac(MAC_BYTES), [0x11; 6]);
+
+    for options in [
+        WakeOptions::new(&packet).trailer([0xAA; 2].as_slice()),
+        WakeOptions::new(&with_password).secure_on([0x22; 6]),
+    ] {
+        let options = options
+            .broadcast_address("192.168.1.255:9")
+            .relay("127.0.0.1:9009");
+
+        assert!(matches!(
+            options.validate().unwrap_err().as_slice(),
+            [WakeError::UnrelayablePacket(_)]
+        ));
+        let error = wake_device(options).expect_err("Relayed a packet the relay rejects");
+        assert!(matches!(
+            error.downcast_ref::<WakeError>(),
+            Some(WakeError::UnrelayablePacket(_))
+        ));
+    }
+
+    // a single SecureOn password is still relayed
+    let options = WakeOptions::new(&packet)
+        .secure_on([0x22; 6])
+        .broadcast_address("192.168.1.255:9")
+        .relay("127.0.0.1:9009");
+    assert!(options.validate().is_ok());
+}
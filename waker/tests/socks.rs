@@ -0,0 +1,99 @@
+// Copyright (C) 2025 DarkCeptor44
+//
+// This file is part of waker.
+//
+// waker is free software: you can redistribute it and/or modify
+// it under theterms of the GNU Lesser General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// waker is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Lesser General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public License
+// along with waker.  If not, see <https://www.gnu.org/licenses/>.
+
+#![cfg(feature = "socks")]
+
+use std::{
+    io::{Read, Write},
+    net::{SocketAddr, TcpListener, UdpSocket},
+    thread,
+};
+use waker::{wake_device, Mac, MagicPacket, WakeOptions};
+
+const MAC_BYTES: [u8; 6] = [0x01, 0x23, 0x45, 0x67, 0x89, 0xAB];
+
+/// Accepts one UDP association and returns the header and payload of the first datagram it relays
+fn fake_proxy(listener: TcpListener, relay: UdpSocket) -> thread::JoinHandle<(Vec<u8>, Vec<u8>)> {
+    thread::spawn(move || {
+        let (mut control, _) = listener.accept().expect("Failed to accept connection");
+
+        let mut greeting = [0u8; 3];
+        control.read_exact(&mut greeting).unwrap();
+        assert_eq!(greeting, [5, 1, 0]);
+        control.write_all(&[5, 0]).unwrap();
+
+        // UDP ASSOCIATE with an IPv4 address
+        let mut request = [0u8; 10];
+        control.read_exact(&mut request).unwrap();
+        assert_eq!(request[..4], [5, 3, 0, 1]);
+
+        let SocketAddr::V4(relay_addr) = relay.local_addr().unwrap() else {
+            unreachable!()
+        };
+        let mut reply = vec![5, 0, 0, 1];
+        reply.extend_from_slice(&relay_addr.ip().octets());
+        reply.extend_from_slice(&relay_addr.port().to_be_bytes());
+        control.write_all(&reply).unwrap();
+
+        let mut buffer = [0u8; 1024];
+        let (len, _) = relay.recv_from(&mut buffer).unwrap();
+        (buffer[..10].to_vec(), buffer[10..len].to_vec())
+    })
+}
+
+#[test]
+fn test_wake_device_through_proxy() {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind listener");
+    let relay = UdpSocket::bind("127.0.0.1:0").expect("Failed to bind relay socket");
+    let proxy_addr = listener.local_addr().unwrap();
+    let handle = fake_proxy(listener, relay);
+
+    let packet = MagicPacket::for_mac(Mac(MAC_BYTES));
+    wake_device(
+        WakeOptions::new(&packet)
+            .broadcast_address("192.168.1.255:9")
+            .proxy(proxy_addr.to_string()),
+    )
+    .expect("Failed to wake device through proxy");
+
+    let (header, payload) = handle.join().expect("Fake proxy panicked");
+    assert_eq!(header, [0, 0, 0, 1, 192, 168, 1, 255, 0, 9]);
+    assert_eq!(payload, packet.0);
+}
+
+#[test]
+fn test_wake_device_proxy_rejects_auth() {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind listener");
+    let proxy_addr = listener.local_addr().unwrap();
+    let handle = thread::spawn(move || {
+        let (mut control, _) = listener.accept().unwrap();
+        let mut greeting = [0u8; 3];
+        control.read_exact(&mut greeting).unwrap();
+        control.write_all(&[5, 0xFF]).unwrap();
+    });
+
+    let packet = MagicPacket::for_mac(Mac(MAC_BYTES));
+    let err = wake_device(
+        WakeOptions::new(&packet)
+            .broadcast_address("192.168.1.255:9")
+            .proxy(proxy_addr.to_string()),
+    )
+    .expect_err("Proxy requiring authentication was accepted");
+
+    handle.join().unwrap();
+    assert!(err.to_string().contains("without authentication"));
+}
@@ -0,0 +1,43 @@
+// Copyright (C) 2025 DarkCeptor44
+//
+// This file is part of waker.
+//
+// waker is free software: you can redistribute it and/or modify
+// it under theterms of the GNU Lesser General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// waker is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Lesser General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public License
+// along with waker.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::{net::TcpListener, time::Duration};
+use waker::{wait_until_reachable, WakeError};
+
+#[test]
+fn test_wait_until_reachable() {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind listener");
+    let addr = listener.local_addr().expect("Failed to get local address");
+
+    let elapsed = wait_until_reachable(&addr.to_string(), Duration::from_secs(1))
+        .expect("Listener was not reachable");
+    assert!(elapsed < Duration::from_secs(1));
+}
+
+#[test]
+fn test_wait_until_reachable_timeout() {
+    // the port is free again once the listener is dropped so connections get refused
+    let addr = TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .expect("Failed to get a free port");
+    let timeout = Duration::from_millis(300);
+
+    assert_eq!(
+        wait_until_reachable(&addr.to_string(), timeout),
+        Err(WakeError::Unreachable { addr, timeout })
+    );
+}